@@ -0,0 +1,429 @@
+use crate::interfaces::irouter::{IHop, IRouter};
+use crate::processor::Processor;
+use crate::schema::{config::Config, pool::Pool};
+use solana_program::{
+  instruction::Instruction, program_error::ProgramError, program_pack::Pack, pubkey::Pubkey,
+  system_program, sysvar,
+};
+use std::cmp::Reverse;
+
+///
+/// A priced path through one or more pools
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct Route {
+  /// The amount of the bid mint going in
+  pub amount: u64,
+  /// The pools of every hop, in order
+  pub pools: Vec<Pubkey>,
+  /// The bid mint followed by the ask mint of every hop
+  pub mints: Vec<Pubkey>,
+  /// The expected output of every hop, the last one being the route output
+  pub amounts: Vec<u64>,
+}
+
+impl Route {
+  /// The expected amount of the ask mint coming out
+  pub fn amount_out(&self) -> u64 {
+    self.amounts.last().copied().unwrap_or(0)
+  }
+}
+
+///
+/// Off-chain pathfinder over a set of decoded pools
+///
+#[derive(Clone, Debug, Default)]
+pub struct Pathfinder {
+  pub pools: Vec<(Pubkey, Pool)>,
+}
+
+impl Pathfinder {
+  pub fn new(pools: Vec<(Pubkey, Pool)>) -> Self {
+    Pathfinder { pools }
+  }
+
+  /// Decode a pool account and add it to the graph
+  pub fn add_pool(&mut self, address: Pubkey, data: &[u8]) -> Result<(), ProgramError> {
+    let pool_data = Pool::unpack(data)?;
+    self.pools.push((address, pool_data));
+    Ok(())
+  }
+
+  /// All routes of up to `max_hops` hops, best output first
+  pub fn find_routes(
+    &self,
+    bid_mint: Pubkey,
+    ask_mint: Pubkey,
+    amount: u64,
+    max_hops: usize,
+  ) -> Vec<Route> {
+    let mut routes = Vec::new();
+    let mut route = Route {
+      amount,
+      pools: vec![],
+      mints: vec![bid_mint],
+      amounts: vec![],
+    };
+    self.search(ask_mint, amount, max_hops, &mut route, &mut routes);
+    routes.sort_by_key(|route| Reverse(route.amount_out()));
+    routes
+  }
+
+  /// The best route, if any
+  pub fn find_best_route(
+    &self,
+    bid_mint: Pubkey,
+    ask_mint: Pubkey,
+    amount: u64,
+    max_hops: usize,
+  ) -> Option<Route> {
+    self
+      .find_routes(bid_mint, ask_mint, amount, max_hops)
+      .into_iter()
+      .next()
+  }
+
+  fn search(
+    &self,
+    ask_mint: Pubkey,
+    amount: u64,
+    max_hops: usize,
+    route: &mut Route,
+    routes: &mut Vec<Route>,
+  ) {
+    if route.pools.len() >= max_hops {
+      return;
+    }
+    let bid_mint = route.mints[route.mints.len() - 1];
+    for (address, pool_data) in self.pools.iter() {
+      if pool_data.is_frozen() || route.pools.contains(address) {
+        continue;
+      }
+      for next_mint in [pool_data.mint_s, pool_data.mint_a, pool_data.mint_b] {
+        // Never come back to a mint already on the path
+        if route.mints.contains(&next_mint) {
+          continue;
+        }
+        let next_amount =
+          match Processor::estimate_ask_amount(pool_data, bid_mint, next_mint, amount) {
+            Ok(next_amount) if next_amount > 0 => next_amount,
+            _ => continue,
+          };
+        route.pools.push(*address);
+        route.mints.push(next_mint);
+        route.amounts.push(next_amount);
+        if next_mint == ask_mint {
+          routes.push(route.clone());
+        } else {
+          self.search(ask_mint, next_amount, max_hops, route, routes);
+        }
+        route.pools.pop();
+        route.mints.pop();
+        route.amounts.pop();
+      }
+    }
+  }
+
+  ///
+  /// Build the RouteMinOut instruction of the router for a route.
+  /// Every hop pays into the payer's associated token account of its ask mint.
+  ///
+  #[allow(clippy::too_many_arguments)]
+  pub fn instruction(
+    &self,
+    route: &Route,
+    min_amount_out: u64,
+    payer: Pubkey,
+    src_acc: Pubkey,
+    splt_program: Pubkey,
+    splata_program: Pubkey,
+    swap_program: Pubkey,
+    program_id: Pubkey,
+  ) -> Result<Instruction, ProgramError> {
+    let mut hops = Vec::with_capacity(route.pools.len());
+    for (i, address) in route.pools.iter().enumerate() {
+      let (_, pool_data) = self
+        .pools
+        .iter()
+        .find(|(key, _)| key == address)
+        .ok_or(ProgramError::InvalidArgument)?;
+      let ask_mint = route.mints[i + 1];
+      // The swap program signs with the pool address as seed
+      let treasurer = Pubkey::create_program_address(&[&address.to_bytes()], &swap_program)
+        .map_err(|_| ProgramError::InvalidSeeds)?;
+      hops.push(IHop {
+        pool_acc: *address,
+        vault_acc: pool_data.vault,
+        treasury_bid_acc: Self::treasury(pool_data, route.mints[i])
+          .ok_or(ProgramError::InvalidArgument)?,
+        dst_acc: Self::associated_address(payer, ask_mint, splt_program, splata_program),
+        mint_ask_acc: ask_mint,
+        treasury_ask_acc: Self::treasury(pool_data, ask_mint)
+          .ok_or(ProgramError::InvalidArgument)?,
+        treasury_sen_acc: pool_data.treasury_s,
+        treasurer,
+      });
+    }
+    IRouter::route_min_out(
+      route.amount,
+      min_amount_out,
+      0,
+      payer,
+      src_acc,
+      &hops,
+      splt_program,
+      splata_program,
+      swap_program,
+      sysvar::rent::id(),
+      system_program::id(),
+      Config::address(&program_id).0,
+      program_id,
+    )
+  }
+
+  fn treasury(pool_data: &Pool, mint: Pubkey) -> Option<Pubkey> {
+    if pool_data.mint_s == mint {
+      return Some(pool_data.treasury_s);
+    }
+    if pool_data.mint_a == mint {
+      return Some(pool_data.treasury_a);
+    }
+    if pool_data.mint_b == mint {
+      return Some(pool_data.treasury_b);
+    }
+    None
+  }
+
+  pub fn associated_address(
+    owner: Pubkey,
+    mint: Pubkey,
+    splt_program: Pubkey,
+    splata_program: Pubkey,
+  ) -> Pubkey {
+    let (address, _) = Pubkey::find_program_address(
+      &[
+        &owner.to_bytes(),
+        &splt_program.to_bytes(),
+        &mint.to_bytes(),
+      ],
+      &splata_program,
+    );
+    address
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::instruction::AppInstruction;
+  use crate::schema::pool::PoolState;
+
+  struct Mints {
+    s: Pubkey,
+    a: Pubkey,
+    b: Pubkey,
+    c: Pubkey,
+  }
+
+  fn mints() -> Mints {
+    Mints {
+      s: Pubkey::new_unique(),
+      a: Pubkey::new_unique(),
+      b: Pubkey::new_unique(),
+      c: Pubkey::new_unique(),
+    }
+  }
+
+  // Pool addresses must be off the curve to derive the treasurer
+  fn pool_address(swap_program: &Pubkey) -> Pubkey {
+    loop {
+      let address = Pubkey::new_unique();
+      if Pubkey::create_program_address(&[&address.to_bytes()], swap_program).is_ok() {
+        return address;
+      }
+    }
+  }
+
+  fn pool(state: PoolState, mints: [Pubkey; 3], reserves: [u64; 3]) -> Pool {
+    Pool {
+      state,
+      vault: Pubkey::new_unique(),
+      mint_s: mints[0],
+      treasury_s: Pubkey::new_unique(),
+      reserve_s: reserves[0],
+      mint_a: mints[1],
+      treasury_a: Pubkey::new_unique(),
+      reserve_a: reserves[1],
+      mint_b: mints[2],
+      treasury_b: Pubkey::new_unique(),
+      reserve_b: reserves[2],
+      ..Pool::default()
+    }
+  }
+
+  // S/A/B and S/B/C are open, the richer S/A/C is frozen unless asked otherwise
+  fn pathfinder(mints: &Mints, swap_program: &Pubkey, state: PoolState) -> Pathfinder {
+    Pathfinder::new(vec![
+      (
+        pool_address(swap_program),
+        pool(
+          PoolState::Initialized,
+          [mints.s, mints.a, mints.b],
+          [1_000_000_000, 1_000_000_000, 1_000_000_000],
+        ),
+      ),
+      (
+        pool_address(swap_program),
+        pool(
+          PoolState::Initialized,
+          [mints.s, mints.b, mints.c],
+          [1_000_000_000, 1_000_000_000, 2_000_000_000],
+        ),
+      ),
+      (
+        pool_address(swap_program),
+        pool(
+          state,
+          [mints.s, mints.a, mints.c],
+          [1_000_000_000, 1_000_000_000, 10_000_000_000],
+        ),
+      ),
+    ])
+  }
+
+  #[test]
+  fn search_skips_frozen_pools() {
+    let mints = mints();
+    let pathfinder = pathfinder(&mints, &Pubkey::new_unique(), PoolState::Frozen);
+    let frozen = pathfinder.pools[2].0;
+    assert!(pathfinder
+      .find_routes(mints.a, mints.c, 1_000_000, 1)
+      .is_empty());
+    let routes = pathfinder.find_routes(mints.a, mints.c, 1_000_000, 3);
+    assert_eq!(routes.len(), 2);
+    assert!(routes.iter().all(|route| !route.pools.contains(&frozen)));
+  }
+
+  #[test]
+  fn search_never_revisits_a_mint_or_a_pool() {
+    let mints = mints();
+    let pathfinder = pathfinder(&mints, &Pubkey::new_unique(), PoolState::Initialized);
+    let routes = pathfinder.find_routes(mints.a, mints.c, 1_000_000, 10);
+    assert!(!routes.is_empty());
+    for route in routes.iter() {
+      for (i, pool) in route.pools.iter().enumerate() {
+        assert!(!route.pools[i + 1..].contains(pool));
+      }
+      for (i, mint) in route.mints.iter().enumerate() {
+        assert!(!route.mints[i + 1..].contains(mint));
+      }
+      assert_eq!(route.mints.len(), route.pools.len() + 1);
+      assert_eq!(route.amounts.len(), route.pools.len());
+      assert_eq!(route.mints[0], mints.a);
+      assert_eq!(route.mints[route.mints.len() - 1], mints.c);
+    }
+  }
+
+  #[test]
+  fn search_respects_max_hops() {
+    let mints = mints();
+    let pathfinder = pathfinder(&mints, &Pubkey::new_unique(), PoolState::Initialized);
+    assert!(pathfinder
+      .find_routes(mints.a, mints.c, 1_000_000, 0)
+      .is_empty());
+    let direct = pathfinder.find_routes(mints.a, mints.c, 1_000_000, 1);
+    assert_eq!(direct.len(), 1);
+    assert_eq!(direct[0].pools, vec![pathfinder.pools[2].0]);
+    for max_hops in 1..4 {
+      let routes = pathfinder.find_routes(mints.a, mints.c, 1_000_000, max_hops);
+      assert!(routes.iter().all(|route| route.pools.len() <= max_hops));
+    }
+  }
+
+  #[test]
+  fn find_routes_ranks_by_output() {
+    let mints = mints();
+    let pathfinder = pathfinder(&mints, &Pubkey::new_unique(), PoolState::Frozen);
+    let routes = pathfinder.find_routes(mints.a, mints.c, 1_000_000, 2);
+    assert_eq!(routes.len(), 2);
+    assert!(routes
+      .windows(2)
+      .all(|pair| pair[0].amount_out() >= pair[1].amount_out()));
+    // Going through the primary mint is exempted from earning
+    assert_eq!(routes[0].mints, vec![mints.a, mints.s, mints.c]);
+    assert_eq!(routes[1].mints, vec![mints.a, mints.b, mints.c]);
+    assert!(routes[0].amount_out() > routes[1].amount_out());
+    assert_eq!(
+      pathfinder.find_best_route(mints.a, mints.c, 1_000_000, 2),
+      Some(routes[0].clone())
+    );
+  }
+
+  #[test]
+  fn instruction_builds_the_hop_accounts() {
+    let mints = mints();
+    let swap_program = Pubkey::new_unique();
+    let pathfinder = pathfinder(&mints, &swap_program, PoolState::Frozen);
+    let route = pathfinder
+      .find_best_route(mints.a, mints.c, 1_000_000, 2)
+      .unwrap();
+    let payer = Pubkey::new_unique();
+    let src_acc = Pubkey::new_unique();
+    let splt_program = Pubkey::new_unique();
+    let splata_program = Pubkey::new_unique();
+    let program_id = Pubkey::new_unique();
+    let ix = pathfinder
+      .instruction(
+        &route,
+        900_000,
+        payer,
+        src_acc,
+        splt_program,
+        splata_program,
+        swap_program,
+        program_id,
+      )
+      .unwrap();
+    assert_eq!(ix.program_id, program_id);
+    assert_eq!(
+      AppInstruction::unpack(&ix.data).unwrap(),
+      AppInstruction::RouteMinOut {
+        amount: 1_000_000,
+        min_amount_out: 900_000,
+        hops: 2,
+        flags: 0,
+      }
+    );
+    assert_eq!(ix.accounts.len(), 8 + 8 * 2);
+    assert_eq!(ix.accounts[0].pubkey, payer);
+    assert!(ix.accounts[0].is_signer);
+    assert_eq!(ix.accounts[1].pubkey, src_acc);
+    assert_eq!(ix.accounts[7].pubkey, Config::address(&program_id).0);
+    // A to S through the first pool, then S to C through the second one
+    let expected = [
+      (&pathfinder.pools[0], mints.a, mints.s),
+      (&pathfinder.pools[1], mints.s, mints.c),
+    ];
+    for (i, &((address, pool_data), bid_mint, ask_mint)) in expected.iter().enumerate() {
+      let keys = ix.accounts[8 + 8 * i..16 + 8 * i]
+        .iter()
+        .map(|account| account.pubkey)
+        .collect::<Vec<Pubkey>>();
+      let treasurer =
+        Pubkey::create_program_address(&[&address.to_bytes()], &swap_program).unwrap();
+      assert_eq!(
+        keys,
+        vec![
+          *address,
+          pool_data.vault,
+          Pathfinder::treasury(pool_data, bid_mint).unwrap(),
+          Pathfinder::associated_address(payer, ask_mint, splt_program, splata_program),
+          ask_mint,
+          Pathfinder::treasury(pool_data, ask_mint).unwrap(),
+          pool_data.treasury_s,
+          treasurer,
+        ]
+      );
+    }
+  }
+}
@@ -1,4 +1,6 @@
 #![cfg(not(feature = "no-entrypoint"))]
+// The entrypoint macro checks the custom-heap and custom-panic features
+#![allow(unexpected_cfgs)]
 
 use crate::{
   error::{AppError, PrintAppError},
@@ -1,3 +1,6 @@
+// The FromPrimitive derive of num-derive 0.3 implements inside a const block
+#![allow(non_local_definitions)]
+
 use num_derive::FromPrimitive as DeriveFromPrimitive;
 use num_traits::FromPrimitive;
 use solana_program::{
@@ -23,6 +26,46 @@ pub enum AppError {
   UnmatchedPrimaryMints,
   #[error("Cannot find reserves")]
   CannotFindReserves,
+  #[error("The required input exceeds the maximum amount")]
+  ExceededMaxAmountIn,
+  #[error("The received amount is less than the minimum")]
+  InsufficientAmountOut,
+  #[error("The route does not end in the destination account")]
+  UnmatchedDestination,
+  #[error("No available route among the candidate pools")]
+  NoAvailableRoute,
+  #[error("Invalid config account")]
+  InvalidConfig,
+  #[error("Cannot initialize the config twice")]
+  ConstructorOnce,
+  #[error("Invalid admin")]
+  InvalidAdmin,
+  #[error("The route exceeds the maximum number of hops")]
+  TooManyHops,
+  #[error("The operation is paused")]
+  Paused,
+  #[error("The pool is not owned by the swap program")]
+  InvalidPoolOwner,
+  #[error("The vault is unmatched in the pool")]
+  UnmatchedVault,
+  #[error("The treasury is unmatched in the pool")]
+  UnmatchedTreasury,
+  #[error("The mint is unmatched in the pool")]
+  UnmatchedMint,
+  #[error("The lpt mint is unmatched in the pool")]
+  UnmatchedMintLpt,
+  #[error("The pool is frozen")]
+  PoolFrozen,
+  #[error("The token account is frozen")]
+  AccountFrozen,
+  #[error("Invalid event data")]
+  InvalidEvent,
+  #[error("The account is not a wrapped SOL account")]
+  NotNativeAccount,
+  #[error("The source account is unmatched in its reserve")]
+  UnmatchedSource,
+  #[error("Invalid upgrade authority")]
+  InvalidUpgradeAuthority,
 }
 
 impl From<AppError> for ProgramError {
@@ -48,6 +91,34 @@ impl PrintProgramError for AppError {
       AppError::Overflow => msg!("Error: Operation overflowed"),
       AppError::UnmatchedPrimaryMints => msg!("Error: The primary mints is unmatched in pools"),
       AppError::CannotFindReserves => msg!("Error: Cannot find reserves"),
+      AppError::ExceededMaxAmountIn => {
+        msg!("Error: The required input exceeds the maximum amount")
+      }
+      AppError::InsufficientAmountOut => {
+        msg!("Error: The received amount is less than the minimum")
+      }
+      AppError::UnmatchedDestination => {
+        msg!("Error: The route does not end in the destination account")
+      }
+      AppError::NoAvailableRoute => {
+        msg!("Error: No available route among the candidate pools")
+      }
+      AppError::InvalidConfig => msg!("Error: Invalid config account"),
+      AppError::ConstructorOnce => msg!("Error: Cannot initialize the config twice"),
+      AppError::InvalidAdmin => msg!("Error: Invalid admin"),
+      AppError::TooManyHops => msg!("Error: The route exceeds the maximum number of hops"),
+      AppError::Paused => msg!("Error: The operation is paused"),
+      AppError::InvalidPoolOwner => msg!("Error: The pool is not owned by the swap program"),
+      AppError::UnmatchedVault => msg!("Error: The vault is unmatched in the pool"),
+      AppError::UnmatchedTreasury => msg!("Error: The treasury is unmatched in the pool"),
+      AppError::UnmatchedMint => msg!("Error: The mint is unmatched in the pool"),
+      AppError::UnmatchedMintLpt => msg!("Error: The lpt mint is unmatched in the pool"),
+      AppError::PoolFrozen => msg!("Error: The pool is frozen"),
+      AppError::AccountFrozen => msg!("Error: The token account is frozen"),
+      AppError::InvalidEvent => msg!("Error: Invalid event data"),
+      AppError::NotNativeAccount => msg!("Error: The account is not a wrapped SOL account"),
+      AppError::UnmatchedSource => msg!("Error: The source account is unmatched in its reserve"),
+      AppError::InvalidUpgradeAuthority => msg!("Error: Invalid upgrade authority"),
     }
   }
 }
@@ -0,0 +1,247 @@
+use crate::error::AppError;
+use solana_program::{log::sol_log_data, program_error::ProgramError, pubkey::Pubkey};
+use std::convert::TryInto;
+
+///
+/// Fill of a single hop
+///
+/// The amounts in and out are measured, while the fee and earning are the
+/// router's oracle estimates on the reserves before the swap, or zero when
+/// the estimate is not available
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Fill {
+  pub pool: Pubkey,
+  pub bid_mint: Pubkey,
+  pub ask_mint: Pubkey,
+  pub amount_in: u64,
+  pub amount_out: u64,
+  pub estimated_fee: u64,
+  pub estimated_earning: u64,
+}
+
+///
+/// Event data
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum EventData {
+  Swap {
+    fills: Vec<Fill>,
+  },
+  Liquidity {
+    pool: Pubkey,
+    lpt: u64,
+    amount_s: u64,
+    amount_a: u64,
+    amount_b: u64,
+  },
+  Admin,
+}
+
+///
+/// Event struct
+///
+/// The layout is version, data tag, instruction tag and authority (the payer
+/// or the admin) followed by the data
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct Event {
+  pub kind: u8,
+  pub authority: Pubkey,
+  pub data: EventData,
+}
+
+///
+/// Event implementation
+///
+impl Event {
+  // Current layout version
+  pub const VERSION: u8 = 1;
+  // Size of a packed fill
+  pub const FILL_LEN: usize = 32 * 3 + 8 * 4;
+
+  // Write the event to the program logs
+  pub fn emit(&self) {
+    sol_log_data(&[&self.pack()]);
+  }
+
+  pub fn pack(&self) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(3 + 32 + 1 + Self::FILL_LEN);
+    buf.push(Self::VERSION);
+    match &self.data {
+      EventData::Swap { .. } => buf.push(0),
+      EventData::Liquidity { .. } => buf.push(1),
+      EventData::Admin => buf.push(2),
+    }
+    buf.push(self.kind);
+    buf.extend_from_slice(self.authority.as_ref());
+    match &self.data {
+      EventData::Swap { fills } => {
+        buf.push(fills.len() as u8);
+        for fill in fills.iter() {
+          buf.extend_from_slice(fill.pool.as_ref());
+          buf.extend_from_slice(fill.bid_mint.as_ref());
+          buf.extend_from_slice(fill.ask_mint.as_ref());
+          buf.extend_from_slice(&fill.amount_in.to_le_bytes());
+          buf.extend_from_slice(&fill.amount_out.to_le_bytes());
+          buf.extend_from_slice(&fill.estimated_fee.to_le_bytes());
+          buf.extend_from_slice(&fill.estimated_earning.to_le_bytes());
+        }
+      }
+      EventData::Liquidity {
+        pool,
+        lpt,
+        amount_s,
+        amount_a,
+        amount_b,
+      } => {
+        buf.extend_from_slice(pool.as_ref());
+        buf.extend_from_slice(&lpt.to_le_bytes());
+        buf.extend_from_slice(&amount_s.to_le_bytes());
+        buf.extend_from_slice(&amount_a.to_le_bytes());
+        buf.extend_from_slice(&amount_b.to_le_bytes());
+      }
+      EventData::Admin => {}
+    }
+    buf
+  }
+
+  pub fn unpack(event: &[u8]) -> Result<Self, ProgramError> {
+    let (&version, rest) = event.split_first().ok_or(AppError::InvalidEvent)?;
+    if version != Self::VERSION {
+      return Err(AppError::InvalidEvent.into());
+    }
+    let (&tag, rest) = rest.split_first().ok_or(AppError::InvalidEvent)?;
+    let (&kind, rest) = rest.split_first().ok_or(AppError::InvalidEvent)?;
+    let authority = read_pubkey(rest, 0)?;
+    let rest = &rest[32..];
+    let data = match tag {
+      0 => {
+        let count = *rest.first().ok_or(AppError::InvalidEvent)? as usize;
+        let mut fills = Vec::with_capacity(count);
+        for i in 0..count {
+          let offset = 1 + i * Self::FILL_LEN;
+          fills.push(Fill {
+            pool: read_pubkey(rest, offset)?,
+            bid_mint: read_pubkey(rest, offset + 32)?,
+            ask_mint: read_pubkey(rest, offset + 64)?,
+            amount_in: read_u64(rest, offset + 96)?,
+            amount_out: read_u64(rest, offset + 104)?,
+            estimated_fee: read_u64(rest, offset + 112)?,
+            estimated_earning: read_u64(rest, offset + 120)?,
+          });
+        }
+        EventData::Swap { fills }
+      }
+      1 => EventData::Liquidity {
+        pool: read_pubkey(rest, 0)?,
+        lpt: read_u64(rest, 32)?,
+        amount_s: read_u64(rest, 40)?,
+        amount_a: read_u64(rest, 48)?,
+        amount_b: read_u64(rest, 56)?,
+      },
+      2 => EventData::Admin,
+      _ => return Err(AppError::InvalidEvent.into()),
+    };
+    Ok(Self {
+      kind,
+      authority,
+      data,
+    })
+  }
+}
+
+fn read_pubkey(src: &[u8], offset: usize) -> Result<Pubkey, ProgramError> {
+  let key = src
+    .get(offset..offset + 32)
+    .and_then(|slice| slice.try_into().ok())
+    .map(Pubkey::new_from_array)
+    .ok_or(AppError::InvalidEvent)?;
+  Ok(key)
+}
+
+fn read_u64(src: &[u8], offset: usize) -> Result<u64, ProgramError> {
+  let value = src
+    .get(offset..offset + 8)
+    .and_then(|slice| slice.try_into().ok())
+    .map(u64::from_le_bytes)
+    .ok_or(AppError::InvalidEvent)?;
+  Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn fill(amount_in: u64) -> Fill {
+    Fill {
+      pool: Pubkey::new_unique(),
+      bid_mint: Pubkey::new_unique(),
+      ask_mint: Pubkey::new_unique(),
+      amount_in,
+      amount_out: amount_in / 2,
+      estimated_fee: 3,
+      estimated_earning: 1,
+    }
+  }
+
+  fn all_events() -> Vec<Event> {
+    vec![
+      Event {
+        kind: 1,
+        authority: Pubkey::new_unique(),
+        data: EventData::Swap {
+          fills: vec![fill(1_000), fill(500)],
+        },
+      },
+      Event {
+        kind: 22,
+        authority: Pubkey::new_unique(),
+        data: EventData::Liquidity {
+          pool: Pubkey::new_unique(),
+          lpt: 1,
+          amount_s: 2,
+          amount_a: 3,
+          amount_b: u64::MAX,
+        },
+      },
+      Event {
+        kind: 15,
+        authority: Pubkey::new_unique(),
+        data: EventData::Admin,
+      },
+    ]
+  }
+
+  #[test]
+  fn pack_unpack_round_trip() {
+    for event in all_events() {
+      assert_eq!(Event::unpack(&event.pack()).unwrap(), event);
+    }
+  }
+
+  #[test]
+  fn pack_swap_length() {
+    let event = &all_events()[0];
+    // Version, data tag, kind, authority, fill count and fills
+    assert_eq!(event.pack().len(), 3 + 32 + 1 + 2 * Event::FILL_LEN);
+  }
+
+  #[test]
+  fn unpack_invalid() {
+    for event in all_events() {
+      let data = event.pack();
+      for len in 0..data.len() {
+        assert!(Event::unpack(&data[..len]).is_err());
+      }
+      // Unknown version
+      let mut data = event.pack();
+      data[0] = Event::VERSION + 1;
+      assert!(Event::unpack(&data).is_err());
+      // Unknown data tag
+      let mut data = event.pack();
+      data[1] = 3;
+      assert!(Event::unpack(&data).is_err());
+    }
+  }
+}
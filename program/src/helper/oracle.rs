@@ -44,4 +44,407 @@ impl Oracle {
     let new_ask_reserve = new_ask_reserve_without_fee.checked_add(fee)?;
     Some((new_ask_reserve, paid_amount, earning))
   }
+
+  pub fn inverse_curve_in_fee(
+    paid_amount: u64,
+    bid_reserve: u64,
+    ask_reserve: u64,
+    is_exempted: bool,
+  ) -> Option<u64> {
+    if paid_amount == 0 || bid_reserve == 0 || paid_amount >= ask_reserve {
+      return None;
+    }
+    let mut deduction = FEE;
+    if !is_exempted {
+      deduction = deduction.checked_add(EARNING)?;
+    }
+    // Smallest amount before fee that still pays out the expected amount after fee
+    let net_rate = DECIMALS.checked_sub(deduction)? as u128;
+    let mut paid_amount_without_fee = (paid_amount as u128)
+      .checked_mul(DECIMALS as u128)?
+      .checked_add(net_rate - 1)?
+      .checked_div(net_rate)? as u64;
+    // Fees are rounded down, so a slightly smaller amount may still be enough
+    while paid_amount_without_fee > 1
+      && Self::net_of_fee(paid_amount_without_fee - 1, is_exempted)? >= paid_amount
+    {
+      paid_amount_without_fee -= 1;
+    }
+    let new_ask_reserve_without_fee = ask_reserve.checked_sub(paid_amount_without_fee)?;
+    if new_ask_reserve_without_fee == 0 {
+      return None;
+    }
+    // Smallest bid reserve that rounds the ask reserve down to the target
+    let new_bid_reserve = (bid_reserve as u128)
+      .checked_mul(ask_reserve as u128)?
+      .checked_div(new_ask_reserve_without_fee as u128 + 1)?
+      .checked_add(1)?;
+    if new_bid_reserve > u64::MAX as u128 {
+      return None;
+    }
+    let new_bid_reserve = new_bid_reserve as u64;
+
+    let (_, estimated_paid_amount, _) =
+      Self::curve_in_fee(new_bid_reserve, bid_reserve, ask_reserve, is_exempted)?;
+    if estimated_paid_amount < paid_amount {
+      return None;
+    }
+    Some(new_bid_reserve)
+  }
+
+  pub fn price_impact(new_bid_reserve: u64, bid_reserve: u64, ask_reserve: u64) -> Option<u64> {
+    let new_ask_reserve = Self::curve(new_bid_reserve, bid_reserve, ask_reserve)?;
+    let paid_amount = ask_reserve.checked_sub(new_ask_reserve)? as u128;
+    // The amount that would be paid at the current spot price
+    let spot_amount = (new_bid_reserve.checked_sub(bid_reserve)? as u128)
+      .checked_mul(ask_reserve as u128)?
+      .checked_div(bid_reserve as u128)?;
+    if spot_amount == 0 {
+      return Some(0);
+    }
+    // Rounding may pay slightly above the spot amount for tiny swaps
+    let price_impact = spot_amount
+      .saturating_sub(paid_amount)
+      .checked_mul(DECIMALS as u128)?
+      .checked_div(spot_amount)? as u64;
+    Some(price_impact)
+  }
+
+  // Splits a single-sided deposit into the amounts to swap for each of the two
+  // other reserves, so that what is left keeps the ratio of the pool
+  pub fn zap_in(
+    amount: u64,
+    bid_reserve: u64,
+    first_reserve: u64,
+    second_reserve: u64,
+    first_exempted: bool,
+    second_exempted: bool,
+  ) -> Option<(u64, u64)> {
+    if amount == 0 || bid_reserve == 0 || first_reserve == 0 || second_reserve == 0 {
+      return None;
+    }
+    // The second swap takes the same share of the bid reserve after the first one
+    let second_amount = |first_amount: u64| -> Option<u64> {
+      let amount = (first_amount as u128)
+        .checked_mul(bid_reserve.checked_add(first_amount)? as u128)?
+        .checked_div(bid_reserve as u128)?;
+      if amount > u64::MAX as u128 {
+        return None;
+      }
+      Some(amount as u64)
+    };
+    // The largest first amount whose left share is still above both received shares
+    let (mut low, mut high) = (0, amount);
+    while low < high {
+      let mid = high - (high - low) / 2;
+      let is_feasible = (|| {
+        let second = second_amount(mid)?;
+        let left_amount = amount.checked_sub(mid)?.checked_sub(second)?;
+        let middle_bid_reserve = bid_reserve.checked_add(mid)?;
+        let new_bid_reserve = middle_bid_reserve.checked_add(second)?;
+        let (new_first_reserve, first_paid, _) = Self::curve_in_fee(
+          middle_bid_reserve,
+          bid_reserve,
+          first_reserve,
+          first_exempted,
+        )?;
+        let (new_second_reserve, second_paid, _) = Self::curve_in_fee(
+          new_bid_reserve,
+          middle_bid_reserve,
+          second_reserve,
+          second_exempted,
+        )?;
+        let is_first_balanced = (left_amount as u128).checked_mul(new_first_reserve as u128)?
+          >= (first_paid as u128).checked_mul(new_bid_reserve as u128)?;
+        let is_second_balanced = (left_amount as u128).checked_mul(new_second_reserve as u128)?
+          >= (second_paid as u128).checked_mul(new_bid_reserve as u128)?;
+        Some(is_first_balanced && is_second_balanced)
+      })()
+      .unwrap_or(false);
+      if is_feasible {
+        low = mid;
+      } else {
+        high = mid - 1;
+      }
+    }
+    if low == 0 {
+      return None;
+    }
+    Some((low, second_amount(low)?))
+  }
+
+  // Caps each delta to the largest amount the ratio of the reserves allows,
+  // empty pools take the deltas as they are
+  pub fn balance_deltas(deltas: [u64; 3], reserves: [u64; 3]) -> Option<[u64; 3]> {
+    if reserves.contains(&0) {
+      return Some(deltas);
+    }
+    // The limiting leg has the smallest delta to reserve ratio
+    let mut k = 0;
+    for i in 1..3 {
+      let lhs = (deltas[i] as u128).checked_mul(reserves[k] as u128)?;
+      let rhs = (deltas[k] as u128).checked_mul(reserves[i] as u128)?;
+      if lhs < rhs {
+        k = i;
+      }
+    }
+    let mut balanced = [0; 3];
+    for i in 0..3 {
+      let amount = (deltas[k] as u128)
+        .checked_mul(reserves[i] as u128)?
+        .checked_div(reserves[k] as u128)?;
+      balanced[i] = std::cmp::min(amount, deltas[i] as u128) as u64;
+    }
+    Some(balanced)
+  }
+
+  fn net_of_fee(amount: u64, is_exempted: bool) -> Option<u64> {
+    let fee = (amount as u128)
+      .checked_mul(FEE as u128)?
+      .checked_div(DECIMALS as u128)? as u64;
+    let mut earning: u64 = 0;
+    if !is_exempted {
+      earning = (amount as u128)
+        .checked_mul(EARNING as u128)?
+        .checked_div(DECIMALS as u128)? as u64;
+    }
+    amount.checked_sub(fee)?.checked_sub(earning)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const RESERVES: [(u64, u64); 4] = [
+    (1_000_000_000, 500_000_000),
+    (500_000_000, 1_000_000_000),
+    (7_919, 104_729),
+    (1_000_000_000_000_000, 3_000_000_000_000),
+  ];
+
+  fn paid_amount(
+    new_bid_reserve: u64,
+    bid_reserve: u64,
+    ask_reserve: u64,
+    is_exempted: bool,
+  ) -> u64 {
+    Oracle::curve_in_fee(new_bid_reserve, bid_reserve, ask_reserve, is_exempted)
+      .map(|(_, paid_amount, _)| paid_amount)
+      .unwrap_or(0)
+  }
+
+  #[test]
+  fn inverse_curve_in_fee_is_the_smallest_input() {
+    for &(bid_reserve, ask_reserve) in RESERVES.iter() {
+      for &is_exempted in [true, false].iter() {
+        for divisor in [1_000_000, 10_000, 1_000, 100, 10, 3, 2].iter() {
+          let target = ask_reserve / divisor;
+          if target == 0 {
+            continue;
+          }
+          let new_bid_reserve =
+            Oracle::inverse_curve_in_fee(target, bid_reserve, ask_reserve, is_exempted).unwrap();
+          assert!(paid_amount(new_bid_reserve, bid_reserve, ask_reserve, is_exempted) >= target);
+          assert!(paid_amount(new_bid_reserve - 1, bid_reserve, ask_reserve, is_exempted) < target);
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn inverse_curve_in_fee_of_small_targets() {
+    let (bid_reserve, ask_reserve) = RESERVES[2];
+    for target in 1..200 {
+      let new_bid_reserve =
+        Oracle::inverse_curve_in_fee(target, bid_reserve, ask_reserve, false).unwrap();
+      assert!(paid_amount(new_bid_reserve, bid_reserve, ask_reserve, false) >= target);
+      assert!(paid_amount(new_bid_reserve - 1, bid_reserve, ask_reserve, false) < target);
+    }
+  }
+
+  #[test]
+  fn inverse_curve_in_fee_of_impossible_targets() {
+    let (bid_reserve, ask_reserve) = RESERVES[0];
+    // Nothing to pay
+    assert_eq!(
+      Oracle::inverse_curve_in_fee(0, bid_reserve, ask_reserve, false),
+      None
+    );
+    // More than the whole reserve
+    assert_eq!(
+      Oracle::inverse_curve_in_fee(ask_reserve, bid_reserve, ask_reserve, false),
+      None
+    );
+    // The whole reserve but one, which the fee makes unreachable
+    assert_eq!(
+      Oracle::inverse_curve_in_fee(ask_reserve - 1, bid_reserve, ask_reserve, false),
+      None
+    );
+    // Empty pools
+    assert_eq!(Oracle::inverse_curve_in_fee(1, 0, ask_reserve, false), None);
+    assert_eq!(Oracle::inverse_curve_in_fee(1, bid_reserve, 0, false), None);
+  }
+
+  // Whether what is left after both swaps is worth at least what each swap paid
+  fn is_balanced(
+    amount: u64,
+    first: u64,
+    reserves: (u64, u64, u64),
+    exemptions: (bool, bool),
+  ) -> bool {
+    let (bid_reserve, first_reserve, second_reserve) = reserves;
+    let second = (first as u128 * (bid_reserve + first) as u128 / bid_reserve as u128) as u64;
+    let left_amount = match amount.checked_sub(first + second) {
+      Some(left_amount) => left_amount,
+      None => return false,
+    };
+    let middle_bid_reserve = bid_reserve + first;
+    let new_bid_reserve = middle_bid_reserve + second;
+    let (new_first_reserve, first_paid, _) =
+      Oracle::curve_in_fee(middle_bid_reserve, bid_reserve, first_reserve, exemptions.0).unwrap();
+    let (new_second_reserve, second_paid, _) = Oracle::curve_in_fee(
+      new_bid_reserve,
+      middle_bid_reserve,
+      second_reserve,
+      exemptions.1,
+    )
+    .unwrap();
+    left_amount as u128 * new_first_reserve as u128 >= first_paid as u128 * new_bid_reserve as u128
+      && left_amount as u128 * new_second_reserve as u128
+        >= second_paid as u128 * new_bid_reserve as u128
+  }
+
+  #[test]
+  fn zap_in_is_the_largest_balanced_split() {
+    for &(bid_reserve, ask_reserve) in RESERVES.iter() {
+      let reserves = (bid_reserve, ask_reserve, ask_reserve / 2);
+      for divisor in [1_000_000, 1_000, 10, 1].iter() {
+        let amount = bid_reserve / divisor;
+        if amount == 0 {
+          continue;
+        }
+        let (first, second) =
+          Oracle::zap_in(amount, reserves.0, reserves.1, reserves.2, false, false).unwrap();
+        assert!(first + second < amount);
+        assert!(is_balanced(amount, first, reserves, (false, false)));
+        assert!(!is_balanced(amount, first + 1, reserves, (false, false)));
+      }
+    }
+  }
+
+  #[test]
+  fn zap_in_with_mixed_exemptions() {
+    let (bid_reserve, ask_reserve) = RESERVES[0];
+    let reserves = (bid_reserve, ask_reserve, ask_reserve);
+    let amount = 300_000_000;
+    let (unexempted, _) =
+      Oracle::zap_in(amount, reserves.0, reserves.1, reserves.2, false, false).unwrap();
+    for &exemptions in [(true, false), (false, true)].iter() {
+      let (first, second) = Oracle::zap_in(
+        amount,
+        reserves.0,
+        reserves.1,
+        reserves.2,
+        exemptions.0,
+        exemptions.1,
+      )
+      .unwrap();
+      assert!(first + second < amount);
+      assert!(is_balanced(amount, first, reserves, exemptions));
+      assert!(!is_balanced(amount, first + 1, reserves, exemptions));
+      // The exempted swap receives more, so less of the deposit is swapped
+      assert!(first < unexempted);
+      assert!(!is_balanced(amount, unexempted, reserves, exemptions));
+    }
+  }
+
+  #[test]
+  fn zap_in_of_small_deposits_splits_in_thirds() {
+    let (bid_reserve, ask_reserve) = RESERVES[0];
+    let amount = 3_000_000;
+    let (first, second) =
+      Oracle::zap_in(amount, bid_reserve, ask_reserve, ask_reserve, false, false).unwrap();
+    // Each swap takes about a third, within the fee and the price impact
+    assert!((first as i64 - amount as i64 / 3).abs() < amount as i64 / 300);
+    assert!((second as i64 - amount as i64 / 3).abs() < amount as i64 / 300);
+  }
+
+  #[test]
+  fn zap_in_of_impossible_deposits() {
+    let (bid_reserve, ask_reserve) = RESERVES[0];
+    let zap_in = |amount, bid_reserve, first_reserve, second_reserve| {
+      Oracle::zap_in(
+        amount,
+        bid_reserve,
+        first_reserve,
+        second_reserve,
+        false,
+        false,
+      )
+    };
+    assert_eq!(zap_in(0, bid_reserve, ask_reserve, ask_reserve), None);
+    assert_eq!(zap_in(1, bid_reserve, ask_reserve, ask_reserve), None);
+    assert_eq!(zap_in(1_000, 0, ask_reserve, ask_reserve), None);
+    assert_eq!(zap_in(1_000, bid_reserve, 0, ask_reserve), None);
+    assert_eq!(zap_in(1_000, bid_reserve, ask_reserve, 0), None);
+  }
+
+  #[test]
+  fn balance_deltas_caps_to_the_limiting_reserve() {
+    let reserves = [1_000, 2_000, 500];
+    // Already balanced
+    assert_eq!(
+      Oracle::balance_deltas([100, 200, 50], reserves),
+      Some([100, 200, 50])
+    );
+    // Each reserve in turn is the limiting one
+    assert_eq!(
+      Oracle::balance_deltas([100, 1_000, 1_000], reserves),
+      Some([100, 200, 50])
+    );
+    assert_eq!(
+      Oracle::balance_deltas([1_000, 200, 1_000], reserves),
+      Some([100, 200, 50])
+    );
+    assert_eq!(
+      Oracle::balance_deltas([1_000, 1_000, 50], reserves),
+      Some([100, 200, 50])
+    );
+    // Nothing of one token means nothing at all
+    assert_eq!(
+      Oracle::balance_deltas([0, 1_000, 1_000], reserves),
+      Some([0, 0, 0])
+    );
+  }
+
+  #[test]
+  fn balance_deltas_never_exceeds_the_deltas() {
+    let reserves = [7_919, 104_729, 1_000_000_007];
+    for &deltas in [[1, 1, 1], [13, 170, 1_650_000], [7_919, 104_728, u64::MAX]].iter() {
+      let balanced = Oracle::balance_deltas(deltas, reserves).unwrap();
+      for i in 0..3 {
+        assert!(balanced[i] <= deltas[i]);
+        // The deposited amounts do not exceed the ratio of the reserves
+        for j in 0..3 {
+          assert!(
+            balanced[i] as u128 * reserves[j] as u128
+              <= (balanced[j] as u128 + 1) * reserves[i] as u128
+          );
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn balance_deltas_of_empty_pools() {
+    assert_eq!(
+      Oracle::balance_deltas([1, 2, 3], [0, 0, 0]),
+      Some([1, 2, 3])
+    );
+    assert_eq!(
+      Oracle::balance_deltas([1, 2, 3], [10, 0, 10]),
+      Some([1, 2, 3])
+    );
+  }
 }
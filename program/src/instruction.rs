@@ -1,17 +1,20 @@
 use crate::error::AppError;
 use solana_program::program_error::ProgramError;
 use std::convert::TryInto;
+use std::mem::size_of;
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum AppInstruction {
   Swap {
     amount: u64,
     limit: u64,
+    flags: u8,
   },
   Route {
     amount: u64,
     first_limit: u64,
     second_limit: u64,
+    flags: u8,
   },
   AddLiquidity {
     delta_s: u64,
@@ -21,8 +24,98 @@ pub enum AppInstruction {
   RemoveLiquidity {
     lpt: u64,
   },
+  MultiRoute {
+    amount: u64,
+    limits: Vec<u64>,
+    flags: u8,
+  },
+  SwapExactOut {
+    amount_out: u64,
+    max_amount_in: u64,
+  },
+  RouteExactOut {
+    amount_out: u64,
+    max_amount_in: u64,
+    hops: u8,
+    flags: u8,
+  },
+  RouteMinOut {
+    amount: u64,
+    min_amount_out: u64,
+    hops: u8,
+    flags: u8,
+  },
+  BridgeRoute {
+    amount: u64,
+    first_limit: u64,
+    bridge_limit: u64,
+    second_limit: u64,
+    flags: u8,
+  },
+  SplitRoute {
+    min_amount_out: u64,
+    paths: Vec<(u64, u8)>,
+    flags: u8,
+  },
+  SmartSwap {
+    amount: u64,
+    min_amount_out: u64,
+    flags: u8,
+  },
+  Quote {
+    amount: u64,
+    hops: u8,
+  },
+  InitializeConfig {
+    max_hops: u8,
+  },
+  UpdateConfig {
+    max_hops: u8,
+  },
+  TransferAdmin,
+  SetPaused {
+    paused: u8,
+  },
+  UpdatePrograms,
+  ZapIn {
+    amount: u64,
+    min_lpt: u64,
+  },
+  ZapOut {
+    lpt: u64,
+    min_amount_out: u64,
+  },
+  MigrateLiquidity {
+    lpt: u64,
+    min_lpt: u64,
+  },
+  AddLiquidityMinOut {
+    delta_s: u64,
+    delta_a: u64,
+    delta_b: u64,
+    min_lpt: u64,
+  },
+  RemoveLiquidityMinOut {
+    lpt: u64,
+    min_s: u64,
+    min_a: u64,
+    min_b: u64,
+  },
+  AddLiquidityBalanced {
+    delta_s: u64,
+    delta_a: u64,
+    delta_b: u64,
+    min_lpt: u64,
+  },
 }
 impl AppInstruction {
+  // Flags of the optional behaviours, given as an optional trailing byte
+  pub const WRAP_BID: u8 = 1 << 0;
+  pub const UNWRAP_ASK: u8 = 1 << 1;
+  pub const CLOSE_TEMPORARY: u8 = 1 << 2;
+  // Most hops a quote can report, 8 + 32 * hops bytes must fit the return data
+  pub const MAX_QUOTE_HOPS: u8 = 31;
+
   pub fn unpack(instruction: &[u8]) -> Result<Self, ProgramError> {
     let (&tag, rest) = instruction
       .split_first()
@@ -39,7 +132,12 @@ impl AppInstruction {
           .and_then(|slice| slice.try_into().ok())
           .map(u64::from_le_bytes)
           .ok_or(AppError::InvalidInstruction)?;
-        Self::Swap { amount, limit }
+        let flags = rest.get(16).copied().unwrap_or(0);
+        Self::Swap {
+          amount,
+          limit,
+          flags,
+        }
       }
       1 => {
         let amount = rest
@@ -57,10 +155,12 @@ impl AppInstruction {
           .and_then(|slice| slice.try_into().ok())
           .map(u64::from_le_bytes)
           .ok_or(AppError::InvalidInstruction)?;
+        let flags = rest.get(24).copied().unwrap_or(0);
         Self::Route {
           amount,
           first_limit,
           second_limit,
+          flags,
         }
       }
       2 => {
@@ -93,7 +193,721 @@ impl AppInstruction {
           .ok_or(AppError::InvalidInstruction)?;
         Self::RemoveLiquidity { lpt }
       }
+      4 => {
+        let amount = rest
+          .get(..8)
+          .and_then(|slice| slice.try_into().ok())
+          .map(u64::from_le_bytes)
+          .ok_or(AppError::InvalidInstruction)?;
+        let hops = rest.get(8).ok_or(AppError::InvalidInstruction)?;
+        if *hops == 0 {
+          return Err(AppError::InvalidInstruction.into());
+        }
+        let limits = (0..*hops as usize)
+          .map(|i| {
+            rest
+              .get(9 + i * 8..17 + i * 8)
+              .and_then(|slice| slice.try_into().ok())
+              .map(u64::from_le_bytes)
+          })
+          .collect::<Option<Vec<u64>>>()
+          .ok_or(AppError::InvalidInstruction)?;
+        let flags = rest.get(9 + limits.len() * 8).copied().unwrap_or(0);
+        Self::MultiRoute {
+          amount,
+          limits,
+          flags,
+        }
+      }
+      5 => {
+        let amount_out = rest
+          .get(..8)
+          .and_then(|slice| slice.try_into().ok())
+          .map(u64::from_le_bytes)
+          .ok_or(AppError::InvalidInstruction)?;
+        let max_amount_in = rest
+          .get(8..16)
+          .and_then(|slice| slice.try_into().ok())
+          .map(u64::from_le_bytes)
+          .ok_or(AppError::InvalidInstruction)?;
+        Self::SwapExactOut {
+          amount_out,
+          max_amount_in,
+        }
+      }
+      6 => {
+        let amount_out = rest
+          .get(..8)
+          .and_then(|slice| slice.try_into().ok())
+          .map(u64::from_le_bytes)
+          .ok_or(AppError::InvalidInstruction)?;
+        let max_amount_in = rest
+          .get(8..16)
+          .and_then(|slice| slice.try_into().ok())
+          .map(u64::from_le_bytes)
+          .ok_or(AppError::InvalidInstruction)?;
+        let hops = *rest.get(16).ok_or(AppError::InvalidInstruction)?;
+        if hops == 0 {
+          return Err(AppError::InvalidInstruction.into());
+        }
+        let flags = rest.get(17).copied().unwrap_or(0);
+        Self::RouteExactOut {
+          amount_out,
+          max_amount_in,
+          hops,
+          flags,
+        }
+      }
+      7 => {
+        let amount = rest
+          .get(..8)
+          .and_then(|slice| slice.try_into().ok())
+          .map(u64::from_le_bytes)
+          .ok_or(AppError::InvalidInstruction)?;
+        let min_amount_out = rest
+          .get(8..16)
+          .and_then(|slice| slice.try_into().ok())
+          .map(u64::from_le_bytes)
+          .ok_or(AppError::InvalidInstruction)?;
+        let hops = *rest.get(16).ok_or(AppError::InvalidInstruction)?;
+        if hops == 0 {
+          return Err(AppError::InvalidInstruction.into());
+        }
+        let flags = rest.get(17).copied().unwrap_or(0);
+        Self::RouteMinOut {
+          amount,
+          min_amount_out,
+          hops,
+          flags,
+        }
+      }
+      8 => {
+        let amount = rest
+          .get(..8)
+          .and_then(|slice| slice.try_into().ok())
+          .map(u64::from_le_bytes)
+          .ok_or(AppError::InvalidInstruction)?;
+        let first_limit = rest
+          .get(8..16)
+          .and_then(|slice| slice.try_into().ok())
+          .map(u64::from_le_bytes)
+          .ok_or(AppError::InvalidInstruction)?;
+        let bridge_limit = rest
+          .get(16..24)
+          .and_then(|slice| slice.try_into().ok())
+          .map(u64::from_le_bytes)
+          .ok_or(AppError::InvalidInstruction)?;
+        let second_limit = rest
+          .get(24..32)
+          .and_then(|slice| slice.try_into().ok())
+          .map(u64::from_le_bytes)
+          .ok_or(AppError::InvalidInstruction)?;
+        let flags = rest.get(32).copied().unwrap_or(0);
+        Self::BridgeRoute {
+          amount,
+          first_limit,
+          bridge_limit,
+          second_limit,
+          flags,
+        }
+      }
+      9 => {
+        let min_amount_out = rest
+          .get(..8)
+          .and_then(|slice| slice.try_into().ok())
+          .map(u64::from_le_bytes)
+          .ok_or(AppError::InvalidInstruction)?;
+        let count = rest.get(8).ok_or(AppError::InvalidInstruction)?;
+        if *count == 0 {
+          return Err(AppError::InvalidInstruction.into());
+        }
+        // Each path is its input amount followed by its hop count
+        let paths = (0..*count as usize)
+          .map(|i| {
+            let amount = rest
+              .get(9 + i * 9..17 + i * 9)
+              .and_then(|slice| slice.try_into().ok())
+              .map(u64::from_le_bytes)?;
+            let hops = *rest.get(17 + i * 9)?;
+            if hops == 0 {
+              return None;
+            }
+            Some((amount, hops))
+          })
+          .collect::<Option<Vec<(u64, u8)>>>()
+          .ok_or(AppError::InvalidInstruction)?;
+        let flags = rest.get(9 + paths.len() * 9).copied().unwrap_or(0);
+        Self::SplitRoute {
+          min_amount_out,
+          paths,
+          flags,
+        }
+      }
+      10 => {
+        let amount = rest
+          .get(..8)
+          .and_then(|slice| slice.try_into().ok())
+          .map(u64::from_le_bytes)
+          .ok_or(AppError::InvalidInstruction)?;
+        let min_amount_out = rest
+          .get(8..16)
+          .and_then(|slice| slice.try_into().ok())
+          .map(u64::from_le_bytes)
+          .ok_or(AppError::InvalidInstruction)?;
+        let flags = rest.get(16).copied().unwrap_or(0);
+        Self::SmartSwap {
+          amount,
+          min_amount_out,
+          flags,
+        }
+      }
+      11 => {
+        let amount = rest
+          .get(..8)
+          .and_then(|slice| slice.try_into().ok())
+          .map(u64::from_le_bytes)
+          .ok_or(AppError::InvalidInstruction)?;
+        let hops = *rest.get(8).ok_or(AppError::InvalidInstruction)?;
+        if hops == 0 || hops > Self::MAX_QUOTE_HOPS {
+          return Err(AppError::InvalidInstruction.into());
+        }
+        Self::Quote { amount, hops }
+      }
+      12 => {
+        let max_hops = *rest.first().ok_or(AppError::InvalidInstruction)?;
+        Self::InitializeConfig { max_hops }
+      }
+      13 => {
+        let max_hops = *rest.first().ok_or(AppError::InvalidInstruction)?;
+        Self::UpdateConfig { max_hops }
+      }
+      14 => Self::TransferAdmin,
+      15 => {
+        let paused = *rest.first().ok_or(AppError::InvalidInstruction)?;
+        Self::SetPaused { paused }
+      }
+      16 => Self::UpdatePrograms,
+      17 => {
+        let amount = rest
+          .get(..8)
+          .and_then(|slice| slice.try_into().ok())
+          .map(u64::from_le_bytes)
+          .ok_or(AppError::InvalidInstruction)?;
+        let min_lpt = rest
+          .get(8..16)
+          .and_then(|slice| slice.try_into().ok())
+          .map(u64::from_le_bytes)
+          .ok_or(AppError::InvalidInstruction)?;
+        Self::ZapIn { amount, min_lpt }
+      }
+      18 => {
+        let lpt = rest
+          .get(..8)
+          .and_then(|slice| slice.try_into().ok())
+          .map(u64::from_le_bytes)
+          .ok_or(AppError::InvalidInstruction)?;
+        let min_amount_out = rest
+          .get(8..16)
+          .and_then(|slice| slice.try_into().ok())
+          .map(u64::from_le_bytes)
+          .ok_or(AppError::InvalidInstruction)?;
+        Self::ZapOut {
+          lpt,
+          min_amount_out,
+        }
+      }
+      19 => {
+        let lpt = rest
+          .get(..8)
+          .and_then(|slice| slice.try_into().ok())
+          .map(u64::from_le_bytes)
+          .ok_or(AppError::InvalidInstruction)?;
+        let min_lpt = rest
+          .get(8..16)
+          .and_then(|slice| slice.try_into().ok())
+          .map(u64::from_le_bytes)
+          .ok_or(AppError::InvalidInstruction)?;
+        Self::MigrateLiquidity { lpt, min_lpt }
+      }
+      20 => {
+        let delta_s = rest
+          .get(..8)
+          .and_then(|slice| slice.try_into().ok())
+          .map(u64::from_le_bytes)
+          .ok_or(AppError::InvalidInstruction)?;
+        let delta_a = rest
+          .get(8..16)
+          .and_then(|slice| slice.try_into().ok())
+          .map(u64::from_le_bytes)
+          .ok_or(AppError::InvalidInstruction)?;
+        let delta_b = rest
+          .get(16..24)
+          .and_then(|slice| slice.try_into().ok())
+          .map(u64::from_le_bytes)
+          .ok_or(AppError::InvalidInstruction)?;
+        let min_lpt = rest
+          .get(24..32)
+          .and_then(|slice| slice.try_into().ok())
+          .map(u64::from_le_bytes)
+          .ok_or(AppError::InvalidInstruction)?;
+        Self::AddLiquidityMinOut {
+          delta_s,
+          delta_a,
+          delta_b,
+          min_lpt,
+        }
+      }
+      21 => {
+        let lpt = rest
+          .get(..8)
+          .and_then(|slice| slice.try_into().ok())
+          .map(u64::from_le_bytes)
+          .ok_or(AppError::InvalidInstruction)?;
+        let min_s = rest
+          .get(8..16)
+          .and_then(|slice| slice.try_into().ok())
+          .map(u64::from_le_bytes)
+          .ok_or(AppError::InvalidInstruction)?;
+        let min_a = rest
+          .get(16..24)
+          .and_then(|slice| slice.try_into().ok())
+          .map(u64::from_le_bytes)
+          .ok_or(AppError::InvalidInstruction)?;
+        let min_b = rest
+          .get(24..32)
+          .and_then(|slice| slice.try_into().ok())
+          .map(u64::from_le_bytes)
+          .ok_or(AppError::InvalidInstruction)?;
+        Self::RemoveLiquidityMinOut {
+          lpt,
+          min_s,
+          min_a,
+          min_b,
+        }
+      }
+      22 => {
+        let delta_s = rest
+          .get(..8)
+          .and_then(|slice| slice.try_into().ok())
+          .map(u64::from_le_bytes)
+          .ok_or(AppError::InvalidInstruction)?;
+        let delta_a = rest
+          .get(8..16)
+          .and_then(|slice| slice.try_into().ok())
+          .map(u64::from_le_bytes)
+          .ok_or(AppError::InvalidInstruction)?;
+        let delta_b = rest
+          .get(16..24)
+          .and_then(|slice| slice.try_into().ok())
+          .map(u64::from_le_bytes)
+          .ok_or(AppError::InvalidInstruction)?;
+        let min_lpt = rest
+          .get(24..32)
+          .and_then(|slice| slice.try_into().ok())
+          .map(u64::from_le_bytes)
+          .ok_or(AppError::InvalidInstruction)?;
+        Self::AddLiquidityBalanced {
+          delta_s,
+          delta_a,
+          delta_b,
+          min_lpt,
+        }
+      }
       _ => return Err(AppError::InvalidInstruction.into()),
     })
   }
+
+  pub fn pack(&self) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(size_of::<Self>());
+    match self {
+      Self::Swap {
+        amount,
+        limit,
+        flags,
+      } => {
+        buf.push(0);
+        buf.extend_from_slice(&amount.to_le_bytes());
+        buf.extend_from_slice(&limit.to_le_bytes());
+        buf.push(*flags);
+      }
+      Self::Route {
+        amount,
+        first_limit,
+        second_limit,
+        flags,
+      } => {
+        buf.push(1);
+        buf.extend_from_slice(&amount.to_le_bytes());
+        buf.extend_from_slice(&first_limit.to_le_bytes());
+        buf.extend_from_slice(&second_limit.to_le_bytes());
+        buf.push(*flags);
+      }
+      Self::AddLiquidity {
+        delta_s,
+        delta_a,
+        delta_b,
+      } => {
+        buf.push(2);
+        buf.extend_from_slice(&delta_s.to_le_bytes());
+        buf.extend_from_slice(&delta_a.to_le_bytes());
+        buf.extend_from_slice(&delta_b.to_le_bytes());
+      }
+      Self::RemoveLiquidity { lpt } => {
+        buf.push(3);
+        buf.extend_from_slice(&lpt.to_le_bytes());
+      }
+      Self::MultiRoute {
+        amount,
+        limits,
+        flags,
+      } => {
+        buf.push(4);
+        buf.extend_from_slice(&amount.to_le_bytes());
+        buf.push(limits.len() as u8);
+        for limit in limits {
+          buf.extend_from_slice(&limit.to_le_bytes());
+        }
+        buf.push(*flags);
+      }
+      Self::SwapExactOut {
+        amount_out,
+        max_amount_in,
+      } => {
+        buf.push(5);
+        buf.extend_from_slice(&amount_out.to_le_bytes());
+        buf.extend_from_slice(&max_amount_in.to_le_bytes());
+      }
+      Self::RouteExactOut {
+        amount_out,
+        max_amount_in,
+        hops,
+        flags,
+      } => {
+        buf.push(6);
+        buf.extend_from_slice(&amount_out.to_le_bytes());
+        buf.extend_from_slice(&max_amount_in.to_le_bytes());
+        buf.push(*hops);
+        buf.push(*flags);
+      }
+      Self::RouteMinOut {
+        amount,
+        min_amount_out,
+        hops,
+        flags,
+      } => {
+        buf.push(7);
+        buf.extend_from_slice(&amount.to_le_bytes());
+        buf.extend_from_slice(&min_amount_out.to_le_bytes());
+        buf.push(*hops);
+        buf.push(*flags);
+      }
+      Self::BridgeRoute {
+        amount,
+        first_limit,
+        bridge_limit,
+        second_limit,
+        flags,
+      } => {
+        buf.push(8);
+        buf.extend_from_slice(&amount.to_le_bytes());
+        buf.extend_from_slice(&first_limit.to_le_bytes());
+        buf.extend_from_slice(&bridge_limit.to_le_bytes());
+        buf.extend_from_slice(&second_limit.to_le_bytes());
+        buf.push(*flags);
+      }
+      Self::SplitRoute {
+        min_amount_out,
+        paths,
+        flags,
+      } => {
+        buf.push(9);
+        buf.extend_from_slice(&min_amount_out.to_le_bytes());
+        buf.push(paths.len() as u8);
+        for (amount, hops) in paths {
+          buf.extend_from_slice(&amount.to_le_bytes());
+          buf.push(*hops);
+        }
+        buf.push(*flags);
+      }
+      Self::SmartSwap {
+        amount,
+        min_amount_out,
+        flags,
+      } => {
+        buf.push(10);
+        buf.extend_from_slice(&amount.to_le_bytes());
+        buf.extend_from_slice(&min_amount_out.to_le_bytes());
+        buf.push(*flags);
+      }
+      Self::Quote { amount, hops } => {
+        buf.push(11);
+        buf.extend_from_slice(&amount.to_le_bytes());
+        buf.push(*hops);
+      }
+      Self::InitializeConfig { max_hops } => {
+        buf.push(12);
+        buf.push(*max_hops);
+      }
+      Self::UpdateConfig { max_hops } => {
+        buf.push(13);
+        buf.push(*max_hops);
+      }
+      Self::TransferAdmin => buf.push(14),
+      Self::SetPaused { paused } => {
+        buf.push(15);
+        buf.push(*paused);
+      }
+      Self::UpdatePrograms => buf.push(16),
+      Self::ZapIn { amount, min_lpt } => {
+        buf.push(17);
+        buf.extend_from_slice(&amount.to_le_bytes());
+        buf.extend_from_slice(&min_lpt.to_le_bytes());
+      }
+      Self::ZapOut {
+        lpt,
+        min_amount_out,
+      } => {
+        buf.push(18);
+        buf.extend_from_slice(&lpt.to_le_bytes());
+        buf.extend_from_slice(&min_amount_out.to_le_bytes());
+      }
+      Self::MigrateLiquidity { lpt, min_lpt } => {
+        buf.push(19);
+        buf.extend_from_slice(&lpt.to_le_bytes());
+        buf.extend_from_slice(&min_lpt.to_le_bytes());
+      }
+      Self::AddLiquidityMinOut {
+        delta_s,
+        delta_a,
+        delta_b,
+        min_lpt,
+      } => {
+        buf.push(20);
+        buf.extend_from_slice(&delta_s.to_le_bytes());
+        buf.extend_from_slice(&delta_a.to_le_bytes());
+        buf.extend_from_slice(&delta_b.to_le_bytes());
+        buf.extend_from_slice(&min_lpt.to_le_bytes());
+      }
+      Self::RemoveLiquidityMinOut {
+        lpt,
+        min_s,
+        min_a,
+        min_b,
+      } => {
+        buf.push(21);
+        buf.extend_from_slice(&lpt.to_le_bytes());
+        buf.extend_from_slice(&min_s.to_le_bytes());
+        buf.extend_from_slice(&min_a.to_le_bytes());
+        buf.extend_from_slice(&min_b.to_le_bytes());
+      }
+      Self::AddLiquidityBalanced {
+        delta_s,
+        delta_a,
+        delta_b,
+        min_lpt,
+      } => {
+        buf.push(22);
+        buf.extend_from_slice(&delta_s.to_le_bytes());
+        buf.extend_from_slice(&delta_a.to_le_bytes());
+        buf.extend_from_slice(&delta_b.to_le_bytes());
+        buf.extend_from_slice(&min_lpt.to_le_bytes());
+      }
+    }
+    buf
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn all_instructions() -> Vec<AppInstruction> {
+    vec![
+      AppInstruction::Swap {
+        amount: 1,
+        limit: 2,
+        flags: AppInstruction::WRAP_BID | AppInstruction::UNWRAP_ASK,
+      },
+      AppInstruction::Route {
+        amount: 1,
+        first_limit: 2,
+        second_limit: 3,
+        flags: AppInstruction::CLOSE_TEMPORARY,
+      },
+      AppInstruction::AddLiquidity {
+        delta_s: 1,
+        delta_a: 2,
+        delta_b: 3,
+      },
+      AppInstruction::RemoveLiquidity { lpt: 1 },
+      AppInstruction::MultiRoute {
+        amount: 1,
+        limits: vec![2, 3, 4],
+        flags: AppInstruction::CLOSE_TEMPORARY,
+      },
+      AppInstruction::SwapExactOut {
+        amount_out: 1,
+        max_amount_in: 2,
+      },
+      AppInstruction::RouteExactOut {
+        amount_out: 1,
+        max_amount_in: 2,
+        hops: 3,
+        flags: AppInstruction::CLOSE_TEMPORARY,
+      },
+      AppInstruction::RouteMinOut {
+        amount: 1,
+        min_amount_out: 2,
+        hops: 3,
+        flags: AppInstruction::CLOSE_TEMPORARY,
+      },
+      AppInstruction::BridgeRoute {
+        amount: 1,
+        first_limit: 2,
+        bridge_limit: 3,
+        second_limit: 4,
+        flags: AppInstruction::CLOSE_TEMPORARY,
+      },
+      AppInstruction::SplitRoute {
+        min_amount_out: 1,
+        paths: vec![(2, 1), (3, 2)],
+        flags: AppInstruction::CLOSE_TEMPORARY,
+      },
+      AppInstruction::SmartSwap {
+        amount: 1,
+        min_amount_out: 2,
+        flags: AppInstruction::CLOSE_TEMPORARY,
+      },
+      AppInstruction::Quote {
+        amount: 1,
+        hops: AppInstruction::MAX_QUOTE_HOPS,
+      },
+      AppInstruction::InitializeConfig { max_hops: 4 },
+      AppInstruction::UpdateConfig { max_hops: 5 },
+      AppInstruction::TransferAdmin,
+      AppInstruction::SetPaused { paused: 0b1010 },
+      AppInstruction::UpdatePrograms,
+      AppInstruction::ZapIn {
+        amount: 1,
+        min_lpt: 2,
+      },
+      AppInstruction::ZapOut {
+        lpt: 1,
+        min_amount_out: 2,
+      },
+      AppInstruction::MigrateLiquidity { lpt: 1, min_lpt: 2 },
+      AppInstruction::AddLiquidityMinOut {
+        delta_s: 1,
+        delta_a: 2,
+        delta_b: 3,
+        min_lpt: 4,
+      },
+      AppInstruction::RemoveLiquidityMinOut {
+        lpt: 1,
+        min_s: 2,
+        min_a: 3,
+        min_b: 4,
+      },
+      AppInstruction::AddLiquidityBalanced {
+        delta_s: 1,
+        delta_a: 2,
+        delta_b: 3,
+        min_lpt: u64::MAX,
+      },
+    ]
+  }
+
+  #[test]
+  fn pack_unpack_round_trip() {
+    let instructions = all_instructions();
+    assert_eq!(instructions.len(), 23);
+    for (tag, instruction) in instructions.into_iter().enumerate() {
+      let data = instruction.pack();
+      assert_eq!(data[0], tag as u8);
+      assert_eq!(AppInstruction::unpack(&data).unwrap(), instruction);
+    }
+  }
+
+  #[test]
+  fn pack_variable_lengths() {
+    let multi_route = AppInstruction::MultiRoute {
+      amount: 1,
+      limits: vec![2, 3, 4],
+      flags: 0,
+    };
+    // Tag, amount, hop count, limits and flags
+    assert_eq!(multi_route.pack().len(), 1 + 8 + 1 + 3 * 8 + 1);
+    let split_route = AppInstruction::SplitRoute {
+      min_amount_out: 1,
+      paths: vec![(2, 1), (3, 2)],
+      flags: 0,
+    };
+    // Tag, minimum, path count, paths and flags
+    assert_eq!(split_route.pack().len(), 1 + 8 + 1 + 2 * 9 + 1);
+  }
+
+  // The optional trailing flags of the instruction, if any
+  fn flags_mut(instruction: &mut AppInstruction) -> Option<&mut u8> {
+    match instruction {
+      AppInstruction::Swap { flags, .. }
+      | AppInstruction::Route { flags, .. }
+      | AppInstruction::MultiRoute { flags, .. }
+      | AppInstruction::RouteExactOut { flags, .. }
+      | AppInstruction::RouteMinOut { flags, .. }
+      | AppInstruction::BridgeRoute { flags, .. }
+      | AppInstruction::SplitRoute { flags, .. }
+      | AppInstruction::SmartSwap { flags, .. } => Some(flags),
+      _ => None,
+    }
+  }
+
+  #[test]
+  fn unpack_without_trailing_flags() {
+    for mut instruction in all_instructions() {
+      let data = instruction.pack();
+      let flags = match flags_mut(&mut instruction) {
+        Some(flags) => flags,
+        None => continue,
+      };
+      assert_ne!(*flags, 0);
+      *flags = 0;
+      let unpacked = AppInstruction::unpack(&data[..data.len() - 1]).unwrap();
+      assert_eq!(unpacked, instruction);
+    }
+  }
+
+  #[test]
+  fn unpack_truncated() {
+    for mut instruction in all_instructions() {
+      let data = instruction.pack();
+      // Every byte but the optional flags is required
+      let required = match flags_mut(&mut instruction) {
+        Some(_) => data.len() - 1,
+        None => data.len(),
+      };
+      for len in 0..required {
+        assert!(AppInstruction::unpack(&data[..len]).is_err());
+      }
+    }
+  }
+
+  #[test]
+  fn unpack_invalid_hops() {
+    // No hops
+    assert!(AppInstruction::unpack(&[4, 1, 0, 0, 0, 0, 0, 0, 0, 0]).is_err());
+    assert!(AppInstruction::unpack(&[9, 1, 0, 0, 0, 0, 0, 0, 0, 0]).is_err());
+    // A path without hops
+    let mut data = vec![9, 1, 0, 0, 0, 0, 0, 0, 0, 1];
+    data.extend_from_slice(&2u64.to_le_bytes());
+    data.push(0);
+    assert!(AppInstruction::unpack(&data).is_err());
+    // Quotes over the return data limit
+    let quote = AppInstruction::Quote {
+      amount: 1,
+      hops: AppInstruction::MAX_QUOTE_HOPS + 1,
+    };
+    assert!(AppInstruction::unpack(&quote.pack()).is_err());
+    // Unknown tag
+    assert!(AppInstruction::unpack(&[23]).is_err());
+  }
 }
@@ -0,0 +1,1276 @@
+use crate::instruction::AppInstruction;
+use crate::schema::account::native_mint;
+use solana_program::{
+  instruction::{AccountMeta, Instruction},
+  program_error::ProgramError,
+  pubkey::Pubkey,
+};
+
+///
+/// Accounts of a single hop in a multi-hop route
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct IHop {
+  pub pool_acc: Pubkey,
+  pub vault_acc: Pubkey,
+  pub treasury_bid_acc: Pubkey,
+  pub dst_acc: Pubkey,
+  pub mint_ask_acc: Pubkey,
+  pub treasury_ask_acc: Pubkey,
+  pub treasury_sen_acc: Pubkey,
+  pub treasurer: Pubkey,
+}
+
+impl IHop {
+  fn push_accounts(&self, accounts: &mut Vec<AccountMeta>) {
+    accounts.push(AccountMeta::new(self.pool_acc, false));
+    accounts.push(AccountMeta::new(self.vault_acc, false));
+    accounts.push(AccountMeta::new(self.treasury_bid_acc, false));
+    accounts.push(AccountMeta::new(self.dst_acc, false));
+    accounts.push(AccountMeta::new_readonly(self.mint_ask_acc, false));
+    accounts.push(AccountMeta::new(self.treasury_ask_acc, false));
+    accounts.push(AccountMeta::new(self.treasury_sen_acc, false));
+    accounts.push(AccountMeta::new_readonly(self.treasurer, false));
+  }
+}
+
+///
+/// Accounts of a candidate pool for on-chain path selection
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ICandidate {
+  pub pool_acc: Pubkey,
+  pub vault_acc: Pubkey,
+  pub treasury_s_acc: Pubkey,
+  pub treasury_a_acc: Pubkey,
+  pub treasury_b_acc: Pubkey,
+  pub treasurer: Pubkey,
+}
+
+impl ICandidate {
+  fn push_accounts(&self, accounts: &mut Vec<AccountMeta>) {
+    accounts.push(AccountMeta::new(self.pool_acc, false));
+    accounts.push(AccountMeta::new(self.vault_acc, false));
+    accounts.push(AccountMeta::new(self.treasury_s_acc, false));
+    accounts.push(AccountMeta::new(self.treasury_a_acc, false));
+    accounts.push(AccountMeta::new(self.treasury_b_acc, false));
+    accounts.push(AccountMeta::new_readonly(self.treasurer, false));
+  }
+}
+
+pub struct IRouter {}
+
+#[allow(clippy::too_many_arguments, clippy::vec_init_then_push)]
+impl IRouter {
+  ///
+  /// Swap
+  ///
+  pub fn swap(
+    amount: u64,
+    limit: u64,
+    flags: u8,
+    payer: Pubkey,
+    pool_acc: Pubkey,
+    vault_acc: Pubkey,
+    src_acc: Pubkey,
+    treasury_bid_acc: Pubkey,
+    dst_acc: Pubkey,
+    mint_ask_acc: Pubkey,
+    treasury_ask_acc: Pubkey,
+    treasury_sen_acc: Pubkey,
+    treasurer: Pubkey,
+    splt_program: Pubkey,
+    splata_program: Pubkey,
+    swap_program: Pubkey,
+    sysvar_rent_acc: Pubkey,
+    system_program: Pubkey,
+    config_acc: Pubkey,
+    program_id: Pubkey,
+  ) -> Result<Instruction, ProgramError> {
+    // Build data
+    let data = AppInstruction::Swap {
+      amount,
+      limit,
+      flags,
+    }
+    .pack();
+    // Build accounts
+    let mut accounts = Vec::with_capacity(17);
+    accounts.push(AccountMeta::new(payer, true));
+    accounts.push(AccountMeta::new(pool_acc, false));
+    accounts.push(AccountMeta::new(vault_acc, false));
+    accounts.push(AccountMeta::new(src_acc, false));
+    accounts.push(AccountMeta::new(treasury_bid_acc, false));
+    accounts.push(AccountMeta::new(dst_acc, false));
+    accounts.push(AccountMeta::new_readonly(mint_ask_acc, false));
+    accounts.push(AccountMeta::new(treasury_ask_acc, false));
+    accounts.push(AccountMeta::new(treasury_sen_acc, false));
+    accounts.push(AccountMeta::new_readonly(treasurer, false));
+    accounts.push(AccountMeta::new_readonly(splt_program, false));
+    accounts.push(AccountMeta::new_readonly(splata_program, false));
+    accounts.push(AccountMeta::new_readonly(swap_program, false));
+    accounts.push(AccountMeta::new_readonly(sysvar_rent_acc, false));
+    accounts.push(AccountMeta::new_readonly(system_program, false));
+    accounts.push(AccountMeta::new_readonly(config_acc, false));
+    // The source account is created from the native mint when wrapping
+    if flags & AppInstruction::WRAP_BID != 0 {
+      accounts.push(AccountMeta::new_readonly(native_mint::id(), false));
+    }
+    // Return
+    Ok(Instruction {
+      program_id,
+      accounts,
+      data,
+    })
+  }
+  ///
+  /// Route
+  ///
+  pub fn route(
+    amount: u64,
+    first_limit: u64,
+    second_limit: u64,
+    flags: u8,
+    payer: Pubkey,
+    first_pool_acc: Pubkey,
+    first_vault_acc: Pubkey,
+    src_acc: Pubkey,
+    mint_bid_acc: Pubkey,
+    treasury_bid_acc: Pubkey,
+    first_treasury_sen_acc: Pubkey,
+    first_treasurer: Pubkey,
+    second_pool_acc: Pubkey,
+    second_vault_acc: Pubkey,
+    dst_acc: Pubkey,
+    mint_ask_acc: Pubkey,
+    treasury_ask_acc: Pubkey,
+    second_treasury_sen_acc: Pubkey,
+    second_treasurer: Pubkey,
+    sen_acc: Pubkey,
+    mint_sen_acc: Pubkey,
+    splt_program: Pubkey,
+    splata_program: Pubkey,
+    swap_program: Pubkey,
+    sysvar_rent_acc: Pubkey,
+    system_program: Pubkey,
+    config_acc: Pubkey,
+    program_id: Pubkey,
+  ) -> Result<Instruction, ProgramError> {
+    // Build data
+    let data = AppInstruction::Route {
+      amount,
+      first_limit,
+      second_limit,
+      flags,
+    }
+    .pack();
+    // Build accounts
+    let mut accounts = Vec::with_capacity(23);
+    accounts.push(AccountMeta::new(payer, true));
+    accounts.push(AccountMeta::new(first_pool_acc, false));
+    accounts.push(AccountMeta::new(first_vault_acc, false));
+    accounts.push(AccountMeta::new(src_acc, false));
+    accounts.push(AccountMeta::new_readonly(mint_bid_acc, false));
+    accounts.push(AccountMeta::new(treasury_bid_acc, false));
+    accounts.push(AccountMeta::new(first_treasury_sen_acc, false));
+    accounts.push(AccountMeta::new_readonly(first_treasurer, false));
+    accounts.push(AccountMeta::new(second_pool_acc, false));
+    accounts.push(AccountMeta::new(second_vault_acc, false));
+    accounts.push(AccountMeta::new(dst_acc, false));
+    accounts.push(AccountMeta::new_readonly(mint_ask_acc, false));
+    accounts.push(AccountMeta::new(treasury_ask_acc, false));
+    accounts.push(AccountMeta::new(second_treasury_sen_acc, false));
+    accounts.push(AccountMeta::new_readonly(second_treasurer, false));
+    accounts.push(AccountMeta::new(sen_acc, false));
+    accounts.push(AccountMeta::new_readonly(mint_sen_acc, false));
+    accounts.push(AccountMeta::new_readonly(splt_program, false));
+    accounts.push(AccountMeta::new_readonly(splata_program, false));
+    accounts.push(AccountMeta::new_readonly(swap_program, false));
+    accounts.push(AccountMeta::new_readonly(sysvar_rent_acc, false));
+    accounts.push(AccountMeta::new_readonly(system_program, false));
+    accounts.push(AccountMeta::new_readonly(config_acc, false));
+    // Return
+    Ok(Instruction {
+      program_id,
+      accounts,
+      data,
+    })
+  }
+  ///
+  /// Add liquidity
+  ///
+  pub fn add_liquidity(
+    delta_s: u64,
+    delta_a: u64,
+    delta_b: u64,
+    payer: Pubkey,
+    pool_acc: Pubkey,
+    lpt_acc: Pubkey,
+    mint_lpt_acc: Pubkey,
+    src_s_acc: Pubkey,
+    treasury_s_acc: Pubkey,
+    src_a_acc: Pubkey,
+    treasury_a_acc: Pubkey,
+    src_b_acc: Pubkey,
+    treasury_b_acc: Pubkey,
+    treasurer: Pubkey,
+    splt_program: Pubkey,
+    splata_program: Pubkey,
+    swap_program: Pubkey,
+    sysvar_rent_acc: Pubkey,
+    system_program: Pubkey,
+    config_acc: Pubkey,
+    program_id: Pubkey,
+  ) -> Result<Instruction, ProgramError> {
+    // Build data
+    let data = AppInstruction::AddLiquidity {
+      delta_s,
+      delta_a,
+      delta_b,
+    }
+    .pack();
+    // Build accounts
+    let mut accounts = Vec::with_capacity(17);
+    accounts.push(AccountMeta::new(payer, true));
+    accounts.push(AccountMeta::new(pool_acc, false));
+    accounts.push(AccountMeta::new(lpt_acc, false));
+    accounts.push(AccountMeta::new(mint_lpt_acc, false));
+    accounts.push(AccountMeta::new(src_s_acc, false));
+    accounts.push(AccountMeta::new(treasury_s_acc, false));
+    accounts.push(AccountMeta::new(src_a_acc, false));
+    accounts.push(AccountMeta::new(treasury_a_acc, false));
+    accounts.push(AccountMeta::new(src_b_acc, false));
+    accounts.push(AccountMeta::new(treasury_b_acc, false));
+    accounts.push(AccountMeta::new_readonly(treasurer, false));
+    accounts.push(AccountMeta::new_readonly(splt_program, false));
+    accounts.push(AccountMeta::new_readonly(splata_program, false));
+    accounts.push(AccountMeta::new_readonly(swap_program, false));
+    accounts.push(AccountMeta::new_readonly(sysvar_rent_acc, false));
+    accounts.push(AccountMeta::new_readonly(system_program, false));
+    accounts.push(AccountMeta::new_readonly(config_acc, false));
+    // Return
+    Ok(Instruction {
+      program_id,
+      accounts,
+      data,
+    })
+  }
+  ///
+  /// Add liquidity with a minimum lpt out
+  ///
+  pub fn add_liquidity_min_out(
+    delta_s: u64,
+    delta_a: u64,
+    delta_b: u64,
+    min_lpt: u64,
+    payer: Pubkey,
+    pool_acc: Pubkey,
+    lpt_acc: Pubkey,
+    mint_lpt_acc: Pubkey,
+    src_s_acc: Pubkey,
+    treasury_s_acc: Pubkey,
+    src_a_acc: Pubkey,
+    treasury_a_acc: Pubkey,
+    src_b_acc: Pubkey,
+    treasury_b_acc: Pubkey,
+    treasurer: Pubkey,
+    splt_program: Pubkey,
+    splata_program: Pubkey,
+    swap_program: Pubkey,
+    sysvar_rent_acc: Pubkey,
+    system_program: Pubkey,
+    config_acc: Pubkey,
+    program_id: Pubkey,
+  ) -> Result<Instruction, ProgramError> {
+    // Build data
+    let data = AppInstruction::AddLiquidityMinOut {
+      delta_s,
+      delta_a,
+      delta_b,
+      min_lpt,
+    }
+    .pack();
+    // Build accounts
+    let mut accounts = Vec::with_capacity(17);
+    accounts.push(AccountMeta::new(payer, true));
+    accounts.push(AccountMeta::new(pool_acc, false));
+    accounts.push(AccountMeta::new(lpt_acc, false));
+    accounts.push(AccountMeta::new(mint_lpt_acc, false));
+    accounts.push(AccountMeta::new(src_s_acc, false));
+    accounts.push(AccountMeta::new(treasury_s_acc, false));
+    accounts.push(AccountMeta::new(src_a_acc, false));
+    accounts.push(AccountMeta::new(treasury_a_acc, false));
+    accounts.push(AccountMeta::new(src_b_acc, false));
+    accounts.push(AccountMeta::new(treasury_b_acc, false));
+    accounts.push(AccountMeta::new_readonly(treasurer, false));
+    accounts.push(AccountMeta::new_readonly(splt_program, false));
+    accounts.push(AccountMeta::new_readonly(splata_program, false));
+    accounts.push(AccountMeta::new_readonly(swap_program, false));
+    accounts.push(AccountMeta::new_readonly(sysvar_rent_acc, false));
+    accounts.push(AccountMeta::new_readonly(system_program, false));
+    accounts.push(AccountMeta::new_readonly(config_acc, false));
+    // Return
+    Ok(Instruction {
+      program_id,
+      accounts,
+      data,
+    })
+  }
+  ///
+  /// Add liquidity capped to the pool ratio
+  ///
+  pub fn add_liquidity_balanced(
+    delta_s: u64,
+    delta_a: u64,
+    delta_b: u64,
+    min_lpt: u64,
+    payer: Pubkey,
+    pool_acc: Pubkey,
+    lpt_acc: Pubkey,
+    mint_lpt_acc: Pubkey,
+    src_s_acc: Pubkey,
+    treasury_s_acc: Pubkey,
+    src_a_acc: Pubkey,
+    treasury_a_acc: Pubkey,
+    src_b_acc: Pubkey,
+    treasury_b_acc: Pubkey,
+    treasurer: Pubkey,
+    splt_program: Pubkey,
+    splata_program: Pubkey,
+    swap_program: Pubkey,
+    sysvar_rent_acc: Pubkey,
+    system_program: Pubkey,
+    config_acc: Pubkey,
+    program_id: Pubkey,
+  ) -> Result<Instruction, ProgramError> {
+    // Build data
+    let data = AppInstruction::AddLiquidityBalanced {
+      delta_s,
+      delta_a,
+      delta_b,
+      min_lpt,
+    }
+    .pack();
+    // Build accounts
+    let mut accounts = Vec::with_capacity(17);
+    accounts.push(AccountMeta::new(payer, true));
+    accounts.push(AccountMeta::new(pool_acc, false));
+    accounts.push(AccountMeta::new(lpt_acc, false));
+    accounts.push(AccountMeta::new(mint_lpt_acc, false));
+    accounts.push(AccountMeta::new(src_s_acc, false));
+    accounts.push(AccountMeta::new(treasury_s_acc, false));
+    accounts.push(AccountMeta::new(src_a_acc, false));
+    accounts.push(AccountMeta::new(treasury_a_acc, false));
+    accounts.push(AccountMeta::new(src_b_acc, false));
+    accounts.push(AccountMeta::new(treasury_b_acc, false));
+    accounts.push(AccountMeta::new_readonly(treasurer, false));
+    accounts.push(AccountMeta::new_readonly(splt_program, false));
+    accounts.push(AccountMeta::new_readonly(splata_program, false));
+    accounts.push(AccountMeta::new_readonly(swap_program, false));
+    accounts.push(AccountMeta::new_readonly(sysvar_rent_acc, false));
+    accounts.push(AccountMeta::new_readonly(system_program, false));
+    accounts.push(AccountMeta::new_readonly(config_acc, false));
+    // Return
+    Ok(Instruction {
+      program_id,
+      accounts,
+      data,
+    })
+  }
+  ///
+  /// Zap in
+  ///
+  pub fn zap_in(
+    amount: u64,
+    min_lpt: u64,
+    payer: Pubkey,
+    pool_acc: Pubkey,
+    vault_acc: Pubkey,
+    src_acc: Pubkey,
+    lpt_acc: Pubkey,
+    mint_lpt_acc: Pubkey,
+    src_s_acc: Pubkey,
+    mint_s_acc: Pubkey,
+    treasury_s_acc: Pubkey,
+    src_a_acc: Pubkey,
+    mint_a_acc: Pubkey,
+    treasury_a_acc: Pubkey,
+    src_b_acc: Pubkey,
+    mint_b_acc: Pubkey,
+    treasury_b_acc: Pubkey,
+    treasurer: Pubkey,
+    splt_program: Pubkey,
+    splata_program: Pubkey,
+    swap_program: Pubkey,
+    sysvar_rent_acc: Pubkey,
+    system_program: Pubkey,
+    config_acc: Pubkey,
+    program_id: Pubkey,
+  ) -> Result<Instruction, ProgramError> {
+    // Build data
+    let data = AppInstruction::ZapIn { amount, min_lpt }.pack();
+    // Build accounts
+    let mut accounts = Vec::with_capacity(22);
+    accounts.push(AccountMeta::new(payer, true));
+    accounts.push(AccountMeta::new(pool_acc, false));
+    accounts.push(AccountMeta::new(vault_acc, false));
+    accounts.push(AccountMeta::new(src_acc, false));
+    accounts.push(AccountMeta::new(lpt_acc, false));
+    accounts.push(AccountMeta::new(mint_lpt_acc, false));
+    accounts.push(AccountMeta::new(src_s_acc, false));
+    accounts.push(AccountMeta::new_readonly(mint_s_acc, false));
+    accounts.push(AccountMeta::new(treasury_s_acc, false));
+    accounts.push(AccountMeta::new(src_a_acc, false));
+    accounts.push(AccountMeta::new_readonly(mint_a_acc, false));
+    accounts.push(AccountMeta::new(treasury_a_acc, false));
+    accounts.push(AccountMeta::new(src_b_acc, false));
+    accounts.push(AccountMeta::new_readonly(mint_b_acc, false));
+    accounts.push(AccountMeta::new(treasury_b_acc, false));
+    accounts.push(AccountMeta::new_readonly(treasurer, false));
+    accounts.push(AccountMeta::new_readonly(splt_program, false));
+    accounts.push(AccountMeta::new_readonly(splata_program, false));
+    accounts.push(AccountMeta::new_readonly(swap_program, false));
+    accounts.push(AccountMeta::new_readonly(sysvar_rent_acc, false));
+    accounts.push(AccountMeta::new_readonly(system_program, false));
+    accounts.push(AccountMeta::new_readonly(config_acc, false));
+    // Return
+    Ok(Instruction {
+      program_id,
+      accounts,
+      data,
+    })
+  }
+  ///
+  /// Zap out
+  ///
+  pub fn zap_out(
+    lpt: u64,
+    min_amount_out: u64,
+    payer: Pubkey,
+    pool_acc: Pubkey,
+    vault_acc: Pubkey,
+    lpt_acc: Pubkey,
+    mint_lpt_acc: Pubkey,
+    mint_ask_acc: Pubkey,
+    dst_s_acc: Pubkey,
+    mint_s_acc: Pubkey,
+    treasury_s_acc: Pubkey,
+    dst_a_acc: Pubkey,
+    mint_a_acc: Pubkey,
+    treasury_a_acc: Pubkey,
+    dst_b_acc: Pubkey,
+    mint_b_acc: Pubkey,
+    treasury_b_acc: Pubkey,
+    treasurer: Pubkey,
+    splt_program: Pubkey,
+    splata_program: Pubkey,
+    swap_program: Pubkey,
+    sysvar_rent_acc: Pubkey,
+    system_program: Pubkey,
+    config_acc: Pubkey,
+    program_id: Pubkey,
+  ) -> Result<Instruction, ProgramError> {
+    // Build data
+    let data = AppInstruction::ZapOut {
+      lpt,
+      min_amount_out,
+    }
+    .pack();
+    // Build accounts
+    let mut accounts = Vec::with_capacity(22);
+    accounts.push(AccountMeta::new(payer, true));
+    accounts.push(AccountMeta::new(pool_acc, false));
+    accounts.push(AccountMeta::new(vault_acc, false));
+    accounts.push(AccountMeta::new(lpt_acc, false));
+    accounts.push(AccountMeta::new(mint_lpt_acc, false));
+    accounts.push(AccountMeta::new_readonly(mint_ask_acc, false));
+    accounts.push(AccountMeta::new(dst_s_acc, false));
+    accounts.push(AccountMeta::new_readonly(mint_s_acc, false));
+    accounts.push(AccountMeta::new(treasury_s_acc, false));
+    accounts.push(AccountMeta::new(dst_a_acc, false));
+    accounts.push(AccountMeta::new_readonly(mint_a_acc, false));
+    accounts.push(AccountMeta::new(treasury_a_acc, false));
+    accounts.push(AccountMeta::new(dst_b_acc, false));
+    accounts.push(AccountMeta::new_readonly(mint_b_acc, false));
+    accounts.push(AccountMeta::new(treasury_b_acc, false));
+    accounts.push(AccountMeta::new_readonly(treasurer, false));
+    accounts.push(AccountMeta::new_readonly(splt_program, false));
+    accounts.push(AccountMeta::new_readonly(splata_program, false));
+    accounts.push(AccountMeta::new_readonly(swap_program, false));
+    accounts.push(AccountMeta::new_readonly(sysvar_rent_acc, false));
+    accounts.push(AccountMeta::new_readonly(system_program, false));
+    accounts.push(AccountMeta::new_readonly(config_acc, false));
+    // Return
+    Ok(Instruction {
+      program_id,
+      accounts,
+      data,
+    })
+  }
+  ///
+  /// Migrate liquidity
+  ///
+  pub fn migrate_liquidity(
+    lpt: u64,
+    min_lpt: u64,
+    payer: Pubkey,
+    old_pool_acc: Pubkey,
+    old_vault_acc: Pubkey,
+    old_lpt_acc: Pubkey,
+    old_mint_lpt_acc: Pubkey,
+    old_treasurer: Pubkey,
+    old_s_acc: Pubkey,
+    old_mint_s_acc: Pubkey,
+    old_treasury_s_acc: Pubkey,
+    old_a_acc: Pubkey,
+    old_mint_a_acc: Pubkey,
+    old_treasury_a_acc: Pubkey,
+    old_b_acc: Pubkey,
+    old_mint_b_acc: Pubkey,
+    old_treasury_b_acc: Pubkey,
+    new_pool_acc: Pubkey,
+    new_lpt_acc: Pubkey,
+    new_mint_lpt_acc: Pubkey,
+    new_treasurer: Pubkey,
+    new_s_acc: Pubkey,
+    new_mint_s_acc: Pubkey,
+    new_treasury_s_acc: Pubkey,
+    new_a_acc: Pubkey,
+    new_mint_a_acc: Pubkey,
+    new_treasury_a_acc: Pubkey,
+    new_b_acc: Pubkey,
+    new_mint_b_acc: Pubkey,
+    new_treasury_b_acc: Pubkey,
+    splt_program: Pubkey,
+    splata_program: Pubkey,
+    swap_program: Pubkey,
+    sysvar_rent_acc: Pubkey,
+    system_program: Pubkey,
+    config_acc: Pubkey,
+    program_id: Pubkey,
+  ) -> Result<Instruction, ProgramError> {
+    // Build data
+    let data = AppInstruction::MigrateLiquidity { lpt, min_lpt }.pack();
+    // Build accounts
+    let mut accounts = Vec::with_capacity(34);
+    accounts.push(AccountMeta::new(payer, true));
+    accounts.push(AccountMeta::new(old_pool_acc, false));
+    accounts.push(AccountMeta::new(old_vault_acc, false));
+    accounts.push(AccountMeta::new(old_lpt_acc, false));
+    accounts.push(AccountMeta::new(old_mint_lpt_acc, false));
+    accounts.push(AccountMeta::new_readonly(old_treasurer, false));
+    accounts.push(AccountMeta::new(old_s_acc, false));
+    accounts.push(AccountMeta::new_readonly(old_mint_s_acc, false));
+    accounts.push(AccountMeta::new(old_treasury_s_acc, false));
+    accounts.push(AccountMeta::new(old_a_acc, false));
+    accounts.push(AccountMeta::new_readonly(old_mint_a_acc, false));
+    accounts.push(AccountMeta::new(old_treasury_a_acc, false));
+    accounts.push(AccountMeta::new(old_b_acc, false));
+    accounts.push(AccountMeta::new_readonly(old_mint_b_acc, false));
+    accounts.push(AccountMeta::new(old_treasury_b_acc, false));
+    accounts.push(AccountMeta::new(new_pool_acc, false));
+    accounts.push(AccountMeta::new(new_lpt_acc, false));
+    accounts.push(AccountMeta::new(new_mint_lpt_acc, false));
+    accounts.push(AccountMeta::new_readonly(new_treasurer, false));
+    accounts.push(AccountMeta::new(new_s_acc, false));
+    accounts.push(AccountMeta::new_readonly(new_mint_s_acc, false));
+    accounts.push(AccountMeta::new(new_treasury_s_acc, false));
+    accounts.push(AccountMeta::new(new_a_acc, false));
+    accounts.push(AccountMeta::new_readonly(new_mint_a_acc, false));
+    accounts.push(AccountMeta::new(new_treasury_a_acc, false));
+    accounts.push(AccountMeta::new(new_b_acc, false));
+    accounts.push(AccountMeta::new_readonly(new_mint_b_acc, false));
+    accounts.push(AccountMeta::new(new_treasury_b_acc, false));
+    accounts.push(AccountMeta::new_readonly(splt_program, false));
+    accounts.push(AccountMeta::new_readonly(splata_program, false));
+    accounts.push(AccountMeta::new_readonly(swap_program, false));
+    accounts.push(AccountMeta::new_readonly(sysvar_rent_acc, false));
+    accounts.push(AccountMeta::new_readonly(system_program, false));
+    accounts.push(AccountMeta::new_readonly(config_acc, false));
+    // Return
+    Ok(Instruction {
+      program_id,
+      accounts,
+      data,
+    })
+  }
+  ///
+  /// Remove liquidity
+  ///
+  pub fn remove_liquidity(
+    lpt: u64,
+    payer: Pubkey,
+    pool_acc: Pubkey,
+    lpt_acc: Pubkey,
+    mint_lpt_acc: Pubkey,
+    dst_s_acc: Pubkey,
+    mint_s_acc: Pubkey,
+    treasury_s_acc: Pubkey,
+    dst_a_acc: Pubkey,
+    mint_a_acc: Pubkey,
+    treasury_a_acc: Pubkey,
+    dst_b_acc: Pubkey,
+    mint_b_acc: Pubkey,
+    treasury_b_acc: Pubkey,
+    treasurer: Pubkey,
+    splt_program: Pubkey,
+    splata_program: Pubkey,
+    swap_program: Pubkey,
+    sysvar_rent_acc: Pubkey,
+    system_program: Pubkey,
+    config_acc: Pubkey,
+    program_id: Pubkey,
+  ) -> Result<Instruction, ProgramError> {
+    // Build data
+    let data = AppInstruction::RemoveLiquidity { lpt }.pack();
+    // Build accounts
+    let mut accounts = Vec::with_capacity(20);
+    accounts.push(AccountMeta::new(payer, true));
+    accounts.push(AccountMeta::new(pool_acc, false));
+    accounts.push(AccountMeta::new(lpt_acc, false));
+    accounts.push(AccountMeta::new(mint_lpt_acc, false));
+    accounts.push(AccountMeta::new(dst_s_acc, false));
+    accounts.push(AccountMeta::new_readonly(mint_s_acc, false));
+    accounts.push(AccountMeta::new(treasury_s_acc, false));
+    accounts.push(AccountMeta::new(dst_a_acc, false));
+    accounts.push(AccountMeta::new_readonly(mint_a_acc, false));
+    accounts.push(AccountMeta::new(treasury_a_acc, false));
+    accounts.push(AccountMeta::new(dst_b_acc, false));
+    accounts.push(AccountMeta::new_readonly(mint_b_acc, false));
+    accounts.push(AccountMeta::new(treasury_b_acc, false));
+    accounts.push(AccountMeta::new_readonly(treasurer, false));
+    accounts.push(AccountMeta::new_readonly(splt_program, false));
+    accounts.push(AccountMeta::new_readonly(splata_program, false));
+    accounts.push(AccountMeta::new_readonly(swap_program, false));
+    accounts.push(AccountMeta::new_readonly(sysvar_rent_acc, false));
+    accounts.push(AccountMeta::new_readonly(system_program, false));
+    accounts.push(AccountMeta::new_readonly(config_acc, false));
+    // Return
+    Ok(Instruction {
+      program_id,
+      accounts,
+      data,
+    })
+  }
+  ///
+  /// Remove liquidity with minimum amounts out
+  ///
+  pub fn remove_liquidity_min_out(
+    lpt: u64,
+    min_s: u64,
+    min_a: u64,
+    min_b: u64,
+    payer: Pubkey,
+    pool_acc: Pubkey,
+    lpt_acc: Pubkey,
+    mint_lpt_acc: Pubkey,
+    dst_s_acc: Pubkey,
+    mint_s_acc: Pubkey,
+    treasury_s_acc: Pubkey,
+    dst_a_acc: Pubkey,
+    mint_a_acc: Pubkey,
+    treasury_a_acc: Pubkey,
+    dst_b_acc: Pubkey,
+    mint_b_acc: Pubkey,
+    treasury_b_acc: Pubkey,
+    treasurer: Pubkey,
+    splt_program: Pubkey,
+    splata_program: Pubkey,
+    swap_program: Pubkey,
+    sysvar_rent_acc: Pubkey,
+    system_program: Pubkey,
+    config_acc: Pubkey,
+    program_id: Pubkey,
+  ) -> Result<Instruction, ProgramError> {
+    // Build data
+    let data = AppInstruction::RemoveLiquidityMinOut {
+      lpt,
+      min_s,
+      min_a,
+      min_b,
+    }
+    .pack();
+    // Build accounts
+    let mut accounts = Vec::with_capacity(20);
+    accounts.push(AccountMeta::new(payer, true));
+    accounts.push(AccountMeta::new(pool_acc, false));
+    accounts.push(AccountMeta::new(lpt_acc, false));
+    accounts.push(AccountMeta::new(mint_lpt_acc, false));
+    accounts.push(AccountMeta::new(dst_s_acc, false));
+    accounts.push(AccountMeta::new_readonly(mint_s_acc, false));
+    accounts.push(AccountMeta::new(treasury_s_acc, false));
+    accounts.push(AccountMeta::new(dst_a_acc, false));
+    accounts.push(AccountMeta::new_readonly(mint_a_acc, false));
+    accounts.push(AccountMeta::new(treasury_a_acc, false));
+    accounts.push(AccountMeta::new(dst_b_acc, false));
+    accounts.push(AccountMeta::new_readonly(mint_b_acc, false));
+    accounts.push(AccountMeta::new(treasury_b_acc, false));
+    accounts.push(AccountMeta::new_readonly(treasurer, false));
+    accounts.push(AccountMeta::new_readonly(splt_program, false));
+    accounts.push(AccountMeta::new_readonly(splata_program, false));
+    accounts.push(AccountMeta::new_readonly(swap_program, false));
+    accounts.push(AccountMeta::new_readonly(sysvar_rent_acc, false));
+    accounts.push(AccountMeta::new_readonly(system_program, false));
+    accounts.push(AccountMeta::new_readonly(config_acc, false));
+    // Return
+    Ok(Instruction {
+      program_id,
+      accounts,
+      data,
+    })
+  }
+  ///
+  /// Multi-hop route
+  ///
+  pub fn multi_route(
+    amount: u64,
+    limits: Vec<u64>,
+    flags: u8,
+    payer: Pubkey,
+    src_acc: Pubkey,
+    hops: &[IHop],
+    splt_program: Pubkey,
+    splata_program: Pubkey,
+    swap_program: Pubkey,
+    sysvar_rent_acc: Pubkey,
+    system_program: Pubkey,
+    config_acc: Pubkey,
+    program_id: Pubkey,
+  ) -> Result<Instruction, ProgramError> {
+    if hops.is_empty() || hops.len() > u8::MAX as usize || hops.len() != limits.len() {
+      return Err(ProgramError::InvalidArgument);
+    }
+    // Build data
+    let data = AppInstruction::MultiRoute {
+      amount,
+      limits,
+      flags,
+    }
+    .pack();
+    // Build accounts
+    let accounts = Self::route_accounts(
+      payer,
+      src_acc,
+      hops,
+      splt_program,
+      splata_program,
+      swap_program,
+      sysvar_rent_acc,
+      system_program,
+      config_acc,
+    );
+    // Return
+    Ok(Instruction {
+      program_id,
+      accounts,
+      data,
+    })
+  }
+  ///
+  /// Multi-hop route with a minimum final output
+  ///
+  pub fn route_min_out(
+    amount: u64,
+    min_amount_out: u64,
+    flags: u8,
+    payer: Pubkey,
+    src_acc: Pubkey,
+    hops: &[IHop],
+    splt_program: Pubkey,
+    splata_program: Pubkey,
+    swap_program: Pubkey,
+    sysvar_rent_acc: Pubkey,
+    system_program: Pubkey,
+    config_acc: Pubkey,
+    program_id: Pubkey,
+  ) -> Result<Instruction, ProgramError> {
+    if hops.is_empty() || hops.len() > u8::MAX as usize {
+      return Err(ProgramError::InvalidArgument);
+    }
+    // Build data
+    let data = AppInstruction::RouteMinOut {
+      amount,
+      min_amount_out,
+      hops: hops.len() as u8,
+      flags,
+    }
+    .pack();
+    // Build accounts
+    let accounts = Self::route_accounts(
+      payer,
+      src_acc,
+      hops,
+      splt_program,
+      splata_program,
+      swap_program,
+      sysvar_rent_acc,
+      system_program,
+      config_acc,
+    );
+    // Return
+    Ok(Instruction {
+      program_id,
+      accounts,
+      data,
+    })
+  }
+  ///
+  /// Swap for an exact amount out
+  ///
+  pub fn swap_exact_out(
+    amount_out: u64,
+    max_amount_in: u64,
+    payer: Pubkey,
+    pool_acc: Pubkey,
+    vault_acc: Pubkey,
+    src_acc: Pubkey,
+    treasury_bid_acc: Pubkey,
+    dst_acc: Pubkey,
+    mint_ask_acc: Pubkey,
+    treasury_ask_acc: Pubkey,
+    treasury_sen_acc: Pubkey,
+    treasurer: Pubkey,
+    splt_program: Pubkey,
+    splata_program: Pubkey,
+    swap_program: Pubkey,
+    sysvar_rent_acc: Pubkey,
+    system_program: Pubkey,
+    config_acc: Pubkey,
+    program_id: Pubkey,
+  ) -> Result<Instruction, ProgramError> {
+    // Build data
+    let data = AppInstruction::SwapExactOut {
+      amount_out,
+      max_amount_in,
+    }
+    .pack();
+    // Build accounts
+    let mut accounts = Vec::with_capacity(16);
+    accounts.push(AccountMeta::new(payer, true));
+    accounts.push(AccountMeta::new(pool_acc, false));
+    accounts.push(AccountMeta::new(vault_acc, false));
+    accounts.push(AccountMeta::new(src_acc, false));
+    accounts.push(AccountMeta::new(treasury_bid_acc, false));
+    accounts.push(AccountMeta::new(dst_acc, false));
+    accounts.push(AccountMeta::new_readonly(mint_ask_acc, false));
+    accounts.push(AccountMeta::new(treasury_ask_acc, false));
+    accounts.push(AccountMeta::new(treasury_sen_acc, false));
+    accounts.push(AccountMeta::new_readonly(treasurer, false));
+    accounts.push(AccountMeta::new_readonly(splt_program, false));
+    accounts.push(AccountMeta::new_readonly(splata_program, false));
+    accounts.push(AccountMeta::new_readonly(swap_program, false));
+    accounts.push(AccountMeta::new_readonly(sysvar_rent_acc, false));
+    accounts.push(AccountMeta::new_readonly(system_program, false));
+    accounts.push(AccountMeta::new_readonly(config_acc, false));
+    // Return
+    Ok(Instruction {
+      program_id,
+      accounts,
+      data,
+    })
+  }
+  ///
+  /// Multi-hop route for an exact final output
+  ///
+  pub fn route_exact_out(
+    amount_out: u64,
+    max_amount_in: u64,
+    flags: u8,
+    payer: Pubkey,
+    src_acc: Pubkey,
+    hops: &[IHop],
+    splt_program: Pubkey,
+    splata_program: Pubkey,
+    swap_program: Pubkey,
+    sysvar_rent_acc: Pubkey,
+    system_program: Pubkey,
+    config_acc: Pubkey,
+    program_id: Pubkey,
+  ) -> Result<Instruction, ProgramError> {
+    if hops.is_empty() || hops.len() > u8::MAX as usize {
+      return Err(ProgramError::InvalidArgument);
+    }
+    // Build data
+    let data = AppInstruction::RouteExactOut {
+      amount_out,
+      max_amount_in,
+      hops: hops.len() as u8,
+      flags,
+    }
+    .pack();
+    // Build accounts
+    let accounts = Self::route_accounts(
+      payer,
+      src_acc,
+      hops,
+      splt_program,
+      splata_program,
+      swap_program,
+      sysvar_rent_acc,
+      system_program,
+      config_acc,
+    );
+    // Return
+    Ok(Instruction {
+      program_id,
+      accounts,
+      data,
+    })
+  }
+  ///
+  /// Route through a bridge pool between two primary pools
+  ///
+  pub fn bridge_route(
+    amount: u64,
+    first_limit: u64,
+    bridge_limit: u64,
+    second_limit: u64,
+    flags: u8,
+    payer: Pubkey,
+    src_acc: Pubkey,
+    first_hop: IHop,
+    bridge_hop: IHop,
+    second_hop: IHop,
+    splt_program: Pubkey,
+    splata_program: Pubkey,
+    swap_program: Pubkey,
+    sysvar_rent_acc: Pubkey,
+    system_program: Pubkey,
+    config_acc: Pubkey,
+    program_id: Pubkey,
+  ) -> Result<Instruction, ProgramError> {
+    // Build data
+    let data = AppInstruction::BridgeRoute {
+      amount,
+      first_limit,
+      bridge_limit,
+      second_limit,
+      flags,
+    }
+    .pack();
+    // Build accounts
+    let accounts = Self::route_accounts(
+      payer,
+      src_acc,
+      &[first_hop, bridge_hop, second_hop],
+      splt_program,
+      splata_program,
+      swap_program,
+      sysvar_rent_acc,
+      system_program,
+      config_acc,
+    );
+    // Return
+    Ok(Instruction {
+      program_id,
+      accounts,
+      data,
+    })
+  }
+  ///
+  /// Split route over several paths to the same destination
+  ///
+  pub fn split_route(
+    min_amount_out: u64,
+    paths: &[(u64, &[IHop])],
+    flags: u8,
+    payer: Pubkey,
+    src_acc: Pubkey,
+    dst_acc: Pubkey,
+    splt_program: Pubkey,
+    splata_program: Pubkey,
+    swap_program: Pubkey,
+    sysvar_rent_acc: Pubkey,
+    system_program: Pubkey,
+    config_acc: Pubkey,
+    program_id: Pubkey,
+  ) -> Result<Instruction, ProgramError> {
+    if paths.is_empty()
+      || paths.len() > u8::MAX as usize
+      || paths
+        .iter()
+        .any(|(_, hops)| hops.is_empty() || hops.len() > u8::MAX as usize)
+    {
+      return Err(ProgramError::InvalidArgument);
+    }
+    // Build data
+    let data = AppInstruction::SplitRoute {
+      min_amount_out,
+      paths: paths
+        .iter()
+        .map(|(amount, hops)| (*amount, hops.len() as u8))
+        .collect(),
+      flags,
+    }
+    .pack();
+    // Build accounts
+    let hops = paths.iter().flat_map(|(_, hops)| hops.iter());
+    let mut accounts = Vec::with_capacity(9 + 8 * hops.clone().count());
+    accounts.push(AccountMeta::new(payer, true));
+    accounts.push(AccountMeta::new(src_acc, false));
+    accounts.push(AccountMeta::new(dst_acc, false));
+    accounts.push(AccountMeta::new_readonly(splt_program, false));
+    accounts.push(AccountMeta::new_readonly(splata_program, false));
+    accounts.push(AccountMeta::new_readonly(swap_program, false));
+    accounts.push(AccountMeta::new_readonly(sysvar_rent_acc, false));
+    accounts.push(AccountMeta::new_readonly(system_program, false));
+    accounts.push(AccountMeta::new_readonly(config_acc, false));
+    for hop in hops {
+      hop.push_accounts(&mut accounts);
+    }
+    // Return
+    Ok(Instruction {
+      program_id,
+      accounts,
+      data,
+    })
+  }
+  ///
+  /// Smart swap over the best of the candidate pools
+  ///
+  pub fn smart_swap(
+    amount: u64,
+    min_amount_out: u64,
+    flags: u8,
+    payer: Pubkey,
+    src_acc: Pubkey,
+    dst_acc: Pubkey,
+    mint_ask_acc: Pubkey,
+    sen_acc: Pubkey,
+    mint_sen_acc: Pubkey,
+    candidates: &[ICandidate],
+    splt_program: Pubkey,
+    splata_program: Pubkey,
+    swap_program: Pubkey,
+    sysvar_rent_acc: Pubkey,
+    system_program: Pubkey,
+    config_acc: Pubkey,
+    program_id: Pubkey,
+  ) -> Result<Instruction, ProgramError> {
+    if candidates.is_empty() {
+      return Err(ProgramError::InvalidArgument);
+    }
+    // Build data
+    let data = AppInstruction::SmartSwap {
+      amount,
+      min_amount_out,
+      flags,
+    }
+    .pack();
+    // Build accounts
+    let mut accounts = Vec::with_capacity(12 + 6 * candidates.len());
+    accounts.push(AccountMeta::new(payer, true));
+    accounts.push(AccountMeta::new(src_acc, false));
+    accounts.push(AccountMeta::new(dst_acc, false));
+    accounts.push(AccountMeta::new_readonly(mint_ask_acc, false));
+    accounts.push(AccountMeta::new(sen_acc, false));
+    accounts.push(AccountMeta::new_readonly(mint_sen_acc, false));
+    accounts.push(AccountMeta::new_readonly(splt_program, false));
+    accounts.push(AccountMeta::new_readonly(splata_program, false));
+    accounts.push(AccountMeta::new_readonly(swap_program, false));
+    accounts.push(AccountMeta::new_readonly(sysvar_rent_acc, false));
+    accounts.push(AccountMeta::new_readonly(system_program, false));
+    accounts.push(AccountMeta::new_readonly(config_acc, false));
+    for candidate in candidates {
+      candidate.push_accounts(&mut accounts);
+    }
+    // Return
+    Ok(Instruction {
+      program_id,
+      accounts,
+      data,
+    })
+  }
+  ///
+  /// Quote a multi-hop route
+  ///
+  pub fn quote(
+    amount: u64,
+    mint_bid_acc: Pubkey,
+    hops: &[(Pubkey, Pubkey)],
+    swap_program: Pubkey,
+    config_acc: Pubkey,
+    program_id: Pubkey,
+  ) -> Result<Instruction, ProgramError> {
+    if hops.is_empty() || hops.len() > AppInstruction::MAX_QUOTE_HOPS as usize {
+      return Err(ProgramError::InvalidArgument);
+    }
+    // Build data
+    let data = AppInstruction::Quote {
+      amount,
+      hops: hops.len() as u8,
+    }
+    .pack();
+    // Build accounts, each hop being its pool and its ask mint
+    let mut accounts = Vec::with_capacity(3 + 2 * hops.len());
+    accounts.push(AccountMeta::new_readonly(mint_bid_acc, false));
+    accounts.push(AccountMeta::new_readonly(swap_program, false));
+    accounts.push(AccountMeta::new_readonly(config_acc, false));
+    for &(pool_acc, mint_ask_acc) in hops {
+      accounts.push(AccountMeta::new_readonly(pool_acc, false));
+      accounts.push(AccountMeta::new_readonly(mint_ask_acc, false));
+    }
+    // Return
+    Ok(Instruction {
+      program_id,
+      accounts,
+      data,
+    })
+  }
+  ///
+  /// Initialize config
+  ///
+  pub fn initialize_config(
+    max_hops: u8,
+    payer: Pubkey,
+    admin: Pubkey,
+    config_acc: Pubkey,
+    system_program: Pubkey,
+    sysvar_rent_acc: Pubkey,
+    swap_program: Pubkey,
+    splt_program: Pubkey,
+    splata_program: Pubkey,
+    program_data_acc: Pubkey,
+    program_id: Pubkey,
+  ) -> Result<Instruction, ProgramError> {
+    // Build data
+    let data = AppInstruction::InitializeConfig { max_hops }.pack();
+    // Build accounts
+    let mut accounts = Vec::with_capacity(9);
+    accounts.push(AccountMeta::new(payer, true));
+    accounts.push(AccountMeta::new_readonly(admin, false));
+    accounts.push(AccountMeta::new(config_acc, false));
+    accounts.push(AccountMeta::new_readonly(system_program, false));
+    accounts.push(AccountMeta::new_readonly(sysvar_rent_acc, false));
+    accounts.push(AccountMeta::new_readonly(swap_program, false));
+    accounts.push(AccountMeta::new_readonly(splt_program, false));
+    accounts.push(AccountMeta::new_readonly(splata_program, false));
+    accounts.push(AccountMeta::new_readonly(program_data_acc, false));
+    // Return
+    Ok(Instruction {
+      program_id,
+      accounts,
+      data,
+    })
+  }
+  ///
+  /// Update config
+  ///
+  pub fn update_config(
+    max_hops: u8,
+    admin: Pubkey,
+    config_acc: Pubkey,
+    program_id: Pubkey,
+  ) -> Result<Instruction, ProgramError> {
+    // Build data
+    let data = AppInstruction::UpdateConfig { max_hops }.pack();
+    // Build accounts
+    let mut accounts = Vec::with_capacity(2);
+    accounts.push(AccountMeta::new_readonly(admin, true));
+    accounts.push(AccountMeta::new(config_acc, false));
+    // Return
+    Ok(Instruction {
+      program_id,
+      accounts,
+      data,
+    })
+  }
+  ///
+  /// Pause operations
+  ///
+  pub fn set_paused(
+    paused: u8,
+    admin: Pubkey,
+    config_acc: Pubkey,
+    program_id: Pubkey,
+  ) -> Result<Instruction, ProgramError> {
+    // Build data
+    let data = AppInstruction::SetPaused { paused }.pack();
+    // Build accounts
+    let mut accounts = Vec::with_capacity(2);
+    accounts.push(AccountMeta::new_readonly(admin, true));
+    accounts.push(AccountMeta::new(config_acc, false));
+    // Return
+    Ok(Instruction {
+      program_id,
+      accounts,
+      data,
+    })
+  }
+  ///
+  /// Update trusted programs
+  ///
+  pub fn update_programs(
+    admin: Pubkey,
+    config_acc: Pubkey,
+    swap_program: Pubkey,
+    splt_program: Pubkey,
+    splata_program: Pubkey,
+    program_id: Pubkey,
+  ) -> Result<Instruction, ProgramError> {
+    // Build data
+    let data = AppInstruction::UpdatePrograms.pack();
+    // Build accounts
+    let mut accounts = Vec::with_capacity(5);
+    accounts.push(AccountMeta::new_readonly(admin, true));
+    accounts.push(AccountMeta::new(config_acc, false));
+    accounts.push(AccountMeta::new_readonly(swap_program, false));
+    accounts.push(AccountMeta::new_readonly(splt_program, false));
+    accounts.push(AccountMeta::new_readonly(splata_program, false));
+    // Return
+    Ok(Instruction {
+      program_id,
+      accounts,
+      data,
+    })
+  }
+  ///
+  /// Transfer admin
+  ///
+  pub fn transfer_admin(
+    admin: Pubkey,
+    config_acc: Pubkey,
+    new_admin: Pubkey,
+    program_id: Pubkey,
+  ) -> Result<Instruction, ProgramError> {
+    // Build data
+    let data = AppInstruction::TransferAdmin.pack();
+    // Build accounts
+    let mut accounts = Vec::with_capacity(3);
+    accounts.push(AccountMeta::new_readonly(admin, true));
+    accounts.push(AccountMeta::new(config_acc, false));
+    accounts.push(AccountMeta::new_readonly(new_admin, false));
+    // Return
+    Ok(Instruction {
+      program_id,
+      accounts,
+      data,
+    })
+  }
+
+  fn route_accounts(
+    payer: Pubkey,
+    src_acc: Pubkey,
+    hops: &[IHop],
+    splt_program: Pubkey,
+    splata_program: Pubkey,
+    swap_program: Pubkey,
+    sysvar_rent_acc: Pubkey,
+    system_program: Pubkey,
+    config_acc: Pubkey,
+  ) -> Vec<AccountMeta> {
+    let mut accounts = Vec::with_capacity(8 + 8 * hops.len());
+    accounts.push(AccountMeta::new(payer, true));
+    accounts.push(AccountMeta::new(src_acc, false));
+    accounts.push(AccountMeta::new_readonly(splt_program, false));
+    accounts.push(AccountMeta::new_readonly(splata_program, false));
+    accounts.push(AccountMeta::new_readonly(swap_program, false));
+    accounts.push(AccountMeta::new_readonly(sysvar_rent_acc, false));
+    accounts.push(AccountMeta::new_readonly(system_program, false));
+    accounts.push(AccountMeta::new_readonly(config_acc, false));
+    for hop in hops {
+      hop.push_accounts(&mut accounts);
+    }
+    accounts
+  }
+}
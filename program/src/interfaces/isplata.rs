@@ -7,6 +7,7 @@ use std::mem::size_of;
 
 pub struct ISPLATA {}
 
+#[allow(clippy::too_many_arguments, clippy::vec_init_then_push)]
 impl ISPLATA {
   ///
   /// Initialize account
@@ -0,0 +1,55 @@
+use solana_program::{
+  instruction::{AccountMeta, Instruction},
+  program_error::ProgramError,
+  pubkey::Pubkey,
+};
+use std::mem::size_of;
+
+pub struct ISPLT {}
+
+#[allow(clippy::too_many_arguments, clippy::vec_init_then_push)]
+impl ISPLT {
+  ///
+  /// Close account
+  ///
+  pub fn close_account(
+    target_acc: Pubkey,
+    dst_acc: Pubkey,
+    owner: Pubkey,
+    program_id: Pubkey,
+  ) -> Result<Instruction, ProgramError> {
+    // Build data
+    let mut data = Vec::with_capacity(size_of::<Self>());
+    // Close Account - Code 9
+    data.push(9);
+    // Build accounts
+    let mut accounts = Vec::with_capacity(3);
+    accounts.push(AccountMeta::new(target_acc, false));
+    accounts.push(AccountMeta::new(dst_acc, false));
+    accounts.push(AccountMeta::new_readonly(owner, true));
+    // Return
+    Ok(Instruction {
+      program_id,
+      accounts,
+      data,
+    })
+  }
+  ///
+  /// Sync native
+  ///
+  pub fn sync_native(target_acc: Pubkey, program_id: Pubkey) -> Result<Instruction, ProgramError> {
+    // Build data
+    let mut data = Vec::with_capacity(size_of::<Self>());
+    // Sync Native - Code 17
+    data.push(17);
+    // Build accounts
+    let mut accounts = Vec::with_capacity(1);
+    accounts.push(AccountMeta::new(target_acc, false));
+    // Return
+    Ok(Instruction {
+      program_id,
+      accounts,
+      data,
+    })
+  }
+}
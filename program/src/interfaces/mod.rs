@@ -1,4 +1,9 @@
+pub mod irouter;
 pub mod isplata;
+pub mod isplt;
 pub mod iswap;
+#[cfg(feature = "cpi")]
+pub mod xrouter;
 pub mod xsplata;
+pub mod xsplt;
 pub mod xswap;
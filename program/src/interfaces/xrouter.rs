@@ -0,0 +1,1469 @@
+use crate::interfaces::irouter::{ICandidate, IHop, IRouter};
+use crate::processor::{Candidate, Hop};
+use solana_program::{
+  account_info::AccountInfo,
+  entrypoint::ProgramResult,
+  program::{get_return_data, invoke, invoke_signed},
+};
+use std::convert::TryInto;
+
+pub struct XRouter {}
+
+#[allow(clippy::too_many_arguments)]
+impl XRouter {
+  ///
+  /// Output of Swap, Route, MultiRoute, SwapExactOut, RouteExactOut,
+  /// RouteMinOut, BridgeRoute, SplitRoute, SmartSwap and ZapOut: the output
+  /// amount followed by the output amount of every hop
+  ///
+  pub fn swap_out(router_program: &AccountInfo) -> Option<(u64, Vec<u64>)> {
+    let words = Self::return_words(router_program)?;
+    let (&amount_out, hops) = words.split_first()?;
+    if hops.is_empty() {
+      return None;
+    }
+    Some((amount_out, hops.to_vec()))
+  }
+  ///
+  /// Output of AddLiquidity, AddLiquidityMinOut, ZapIn and MigrateLiquidity:
+  /// the minted lpt
+  ///
+  pub fn lpt_out(router_program: &AccountInfo) -> Option<u64> {
+    match Self::return_words(router_program)?[..] {
+      [lpt] => Some(lpt),
+      _ => None,
+    }
+  }
+  ///
+  /// Output of AddLiquidityBalanced: the minted lpt followed by the unused
+  /// amounts of s, a and b
+  ///
+  pub fn balanced_out(router_program: &AccountInfo) -> Option<(u64, [u64; 3])> {
+    match Self::return_words(router_program)?[..] {
+      [lpt, unused_s, unused_a, unused_b] => Some((lpt, [unused_s, unused_a, unused_b])),
+      _ => None,
+    }
+  }
+  ///
+  /// Output of RemoveLiquidity and RemoveLiquidityMinOut: the withdrawn
+  /// amounts of s, a and b
+  ///
+  pub fn liquidity_out(router_program: &AccountInfo) -> Option<[u64; 3]> {
+    match Self::return_words(router_program)?[..] {
+      [amount_s, amount_a, amount_b] => Some([amount_s, amount_a, amount_b]),
+      _ => None,
+    }
+  }
+  ///
+  /// Output of Quote: the final amount followed by the ask amount, fee,
+  /// earning and price impact of every hop
+  ///
+  pub fn quote_out(router_program: &AccountInfo) -> Option<(u64, Vec<[u64; 4]>)> {
+    let words = Self::return_words(router_program)?;
+    let (&amount_out, hops) = words.split_first()?;
+    if hops.is_empty() || hops.len() % 4 != 0 {
+      return None;
+    }
+    let hops = hops
+      .chunks(4)
+      .map(|hop| [hop[0], hop[1], hop[2], hop[3]])
+      .collect();
+    Some((amount_out, hops))
+  }
+  ///
+  /// Swap
+  ///
+  pub fn swap<'a>(
+    amount: u64,
+    limit: u64,
+    payer: &AccountInfo<'a>,
+    pool_acc: &AccountInfo<'a>,
+    vault_acc: &AccountInfo<'a>,
+    src_acc: &AccountInfo<'a>,
+    treasury_bid_acc: &AccountInfo<'a>,
+    dst_acc: &AccountInfo<'a>,
+    mint_ask_acc: &AccountInfo<'a>,
+    treasury_ask_acc: &AccountInfo<'a>,
+    treasury_sen_acc: &AccountInfo<'a>,
+    treasurer: &AccountInfo<'a>,
+    splt_program: &AccountInfo<'a>,
+    splata_program: &AccountInfo<'a>,
+    swap_program: &AccountInfo<'a>,
+    sysvar_rent_acc: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    config_acc: &AccountInfo<'a>,
+    router_program: &AccountInfo<'a>,
+    seed: &[&[&[u8]]],
+  ) -> ProgramResult {
+    // Calling programs hold wrapped SOL themselves
+    let ix = IRouter::swap(
+      amount,
+      limit,
+      0,
+      *payer.key,
+      *pool_acc.key,
+      *vault_acc.key,
+      *src_acc.key,
+      *treasury_bid_acc.key,
+      *dst_acc.key,
+      *mint_ask_acc.key,
+      *treasury_ask_acc.key,
+      *treasury_sen_acc.key,
+      *treasurer.key,
+      *splt_program.key,
+      *splata_program.key,
+      *swap_program.key,
+      *sysvar_rent_acc.key,
+      *system_program.key,
+      *config_acc.key,
+      *router_program.key,
+    )?;
+    invoke_signed(
+      &ix,
+      &[
+        payer.clone(),
+        pool_acc.clone(),
+        vault_acc.clone(),
+        src_acc.clone(),
+        treasury_bid_acc.clone(),
+        dst_acc.clone(),
+        mint_ask_acc.clone(),
+        treasury_ask_acc.clone(),
+        treasury_sen_acc.clone(),
+        treasurer.clone(),
+        splt_program.clone(),
+        splata_program.clone(),
+        swap_program.clone(),
+        sysvar_rent_acc.clone(),
+        system_program.clone(),
+        config_acc.clone(),
+        router_program.clone(),
+      ],
+      seed,
+    )?;
+    Ok(())
+  }
+  ///
+  /// Route
+  ///
+  pub fn route<'a>(
+    amount: u64,
+    first_limit: u64,
+    second_limit: u64,
+    flags: u8,
+    payer: &AccountInfo<'a>,
+    first_pool_acc: &AccountInfo<'a>,
+    first_vault_acc: &AccountInfo<'a>,
+    src_acc: &AccountInfo<'a>,
+    mint_bid_acc: &AccountInfo<'a>,
+    treasury_bid_acc: &AccountInfo<'a>,
+    first_treasury_sen_acc: &AccountInfo<'a>,
+    first_treasurer: &AccountInfo<'a>,
+    second_pool_acc: &AccountInfo<'a>,
+    second_vault_acc: &AccountInfo<'a>,
+    dst_acc: &AccountInfo<'a>,
+    mint_ask_acc: &AccountInfo<'a>,
+    treasury_ask_acc: &AccountInfo<'a>,
+    second_treasury_sen_acc: &AccountInfo<'a>,
+    second_treasurer: &AccountInfo<'a>,
+    sen_acc: &AccountInfo<'a>,
+    mint_sen_acc: &AccountInfo<'a>,
+    splt_program: &AccountInfo<'a>,
+    splata_program: &AccountInfo<'a>,
+    swap_program: &AccountInfo<'a>,
+    sysvar_rent_acc: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    config_acc: &AccountInfo<'a>,
+    router_program: &AccountInfo<'a>,
+    seed: &[&[&[u8]]],
+  ) -> ProgramResult {
+    let ix = IRouter::route(
+      amount,
+      first_limit,
+      second_limit,
+      flags,
+      *payer.key,
+      *first_pool_acc.key,
+      *first_vault_acc.key,
+      *src_acc.key,
+      *mint_bid_acc.key,
+      *treasury_bid_acc.key,
+      *first_treasury_sen_acc.key,
+      *first_treasurer.key,
+      *second_pool_acc.key,
+      *second_vault_acc.key,
+      *dst_acc.key,
+      *mint_ask_acc.key,
+      *treasury_ask_acc.key,
+      *second_treasury_sen_acc.key,
+      *second_treasurer.key,
+      *sen_acc.key,
+      *mint_sen_acc.key,
+      *splt_program.key,
+      *splata_program.key,
+      *swap_program.key,
+      *sysvar_rent_acc.key,
+      *system_program.key,
+      *config_acc.key,
+      *router_program.key,
+    )?;
+    invoke_signed(
+      &ix,
+      &[
+        payer.clone(),
+        first_pool_acc.clone(),
+        first_vault_acc.clone(),
+        src_acc.clone(),
+        mint_bid_acc.clone(),
+        treasury_bid_acc.clone(),
+        first_treasury_sen_acc.clone(),
+        first_treasurer.clone(),
+        second_pool_acc.clone(),
+        second_vault_acc.clone(),
+        dst_acc.clone(),
+        mint_ask_acc.clone(),
+        treasury_ask_acc.clone(),
+        second_treasury_sen_acc.clone(),
+        second_treasurer.clone(),
+        sen_acc.clone(),
+        mint_sen_acc.clone(),
+        splt_program.clone(),
+        splata_program.clone(),
+        swap_program.clone(),
+        sysvar_rent_acc.clone(),
+        system_program.clone(),
+        config_acc.clone(),
+        router_program.clone(),
+      ],
+      seed,
+    )?;
+    Ok(())
+  }
+  ///
+  /// Swap for an exact amount out
+  ///
+  pub fn swap_exact_out<'a>(
+    amount_out: u64,
+    max_amount_in: u64,
+    payer: &AccountInfo<'a>,
+    pool_acc: &AccountInfo<'a>,
+    vault_acc: &AccountInfo<'a>,
+    src_acc: &AccountInfo<'a>,
+    treasury_bid_acc: &AccountInfo<'a>,
+    dst_acc: &AccountInfo<'a>,
+    mint_ask_acc: &AccountInfo<'a>,
+    treasury_ask_acc: &AccountInfo<'a>,
+    treasury_sen_acc: &AccountInfo<'a>,
+    treasurer: &AccountInfo<'a>,
+    splt_program: &AccountInfo<'a>,
+    splata_program: &AccountInfo<'a>,
+    swap_program: &AccountInfo<'a>,
+    sysvar_rent_acc: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    config_acc: &AccountInfo<'a>,
+    router_program: &AccountInfo<'a>,
+    seed: &[&[&[u8]]],
+  ) -> ProgramResult {
+    let ix = IRouter::swap_exact_out(
+      amount_out,
+      max_amount_in,
+      *payer.key,
+      *pool_acc.key,
+      *vault_acc.key,
+      *src_acc.key,
+      *treasury_bid_acc.key,
+      *dst_acc.key,
+      *mint_ask_acc.key,
+      *treasury_ask_acc.key,
+      *treasury_sen_acc.key,
+      *treasurer.key,
+      *splt_program.key,
+      *splata_program.key,
+      *swap_program.key,
+      *sysvar_rent_acc.key,
+      *system_program.key,
+      *config_acc.key,
+      *router_program.key,
+    )?;
+    invoke_signed(
+      &ix,
+      &[
+        payer.clone(),
+        pool_acc.clone(),
+        vault_acc.clone(),
+        src_acc.clone(),
+        treasury_bid_acc.clone(),
+        dst_acc.clone(),
+        mint_ask_acc.clone(),
+        treasury_ask_acc.clone(),
+        treasury_sen_acc.clone(),
+        treasurer.clone(),
+        splt_program.clone(),
+        splata_program.clone(),
+        swap_program.clone(),
+        sysvar_rent_acc.clone(),
+        system_program.clone(),
+        config_acc.clone(),
+        router_program.clone(),
+      ],
+      seed,
+    )?;
+    Ok(())
+  }
+  ///
+  /// Add liquidity
+  ///
+  pub fn add_liquidity<'a>(
+    delta_s: u64,
+    delta_a: u64,
+    delta_b: u64,
+    payer: &AccountInfo<'a>,
+    pool_acc: &AccountInfo<'a>,
+    lpt_acc: &AccountInfo<'a>,
+    mint_lpt_acc: &AccountInfo<'a>,
+    src_s_acc: &AccountInfo<'a>,
+    treasury_s_acc: &AccountInfo<'a>,
+    src_a_acc: &AccountInfo<'a>,
+    treasury_a_acc: &AccountInfo<'a>,
+    src_b_acc: &AccountInfo<'a>,
+    treasury_b_acc: &AccountInfo<'a>,
+    treasurer: &AccountInfo<'a>,
+    splt_program: &AccountInfo<'a>,
+    splata_program: &AccountInfo<'a>,
+    swap_program: &AccountInfo<'a>,
+    sysvar_rent_acc: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    config_acc: &AccountInfo<'a>,
+    router_program: &AccountInfo<'a>,
+    seed: &[&[&[u8]]],
+  ) -> ProgramResult {
+    let ix = IRouter::add_liquidity(
+      delta_s,
+      delta_a,
+      delta_b,
+      *payer.key,
+      *pool_acc.key,
+      *lpt_acc.key,
+      *mint_lpt_acc.key,
+      *src_s_acc.key,
+      *treasury_s_acc.key,
+      *src_a_acc.key,
+      *treasury_a_acc.key,
+      *src_b_acc.key,
+      *treasury_b_acc.key,
+      *treasurer.key,
+      *splt_program.key,
+      *splata_program.key,
+      *swap_program.key,
+      *sysvar_rent_acc.key,
+      *system_program.key,
+      *config_acc.key,
+      *router_program.key,
+    )?;
+    invoke_signed(
+      &ix,
+      &[
+        payer.clone(),
+        pool_acc.clone(),
+        lpt_acc.clone(),
+        mint_lpt_acc.clone(),
+        src_s_acc.clone(),
+        treasury_s_acc.clone(),
+        src_a_acc.clone(),
+        treasury_a_acc.clone(),
+        src_b_acc.clone(),
+        treasury_b_acc.clone(),
+        treasurer.clone(),
+        splt_program.clone(),
+        splata_program.clone(),
+        swap_program.clone(),
+        sysvar_rent_acc.clone(),
+        system_program.clone(),
+        config_acc.clone(),
+        router_program.clone(),
+      ],
+      seed,
+    )?;
+    Ok(())
+  }
+  ///
+  /// Add liquidity with a minimum lpt out
+  ///
+  pub fn add_liquidity_min_out<'a>(
+    delta_s: u64,
+    delta_a: u64,
+    delta_b: u64,
+    min_lpt: u64,
+    payer: &AccountInfo<'a>,
+    pool_acc: &AccountInfo<'a>,
+    lpt_acc: &AccountInfo<'a>,
+    mint_lpt_acc: &AccountInfo<'a>,
+    src_s_acc: &AccountInfo<'a>,
+    treasury_s_acc: &AccountInfo<'a>,
+    src_a_acc: &AccountInfo<'a>,
+    treasury_a_acc: &AccountInfo<'a>,
+    src_b_acc: &AccountInfo<'a>,
+    treasury_b_acc: &AccountInfo<'a>,
+    treasurer: &AccountInfo<'a>,
+    splt_program: &AccountInfo<'a>,
+    splata_program: &AccountInfo<'a>,
+    swap_program: &AccountInfo<'a>,
+    sysvar_rent_acc: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    config_acc: &AccountInfo<'a>,
+    router_program: &AccountInfo<'a>,
+    seed: &[&[&[u8]]],
+  ) -> ProgramResult {
+    let ix = IRouter::add_liquidity_min_out(
+      delta_s,
+      delta_a,
+      delta_b,
+      min_lpt,
+      *payer.key,
+      *pool_acc.key,
+      *lpt_acc.key,
+      *mint_lpt_acc.key,
+      *src_s_acc.key,
+      *treasury_s_acc.key,
+      *src_a_acc.key,
+      *treasury_a_acc.key,
+      *src_b_acc.key,
+      *treasury_b_acc.key,
+      *treasurer.key,
+      *splt_program.key,
+      *splata_program.key,
+      *swap_program.key,
+      *sysvar_rent_acc.key,
+      *system_program.key,
+      *config_acc.key,
+      *router_program.key,
+    )?;
+    invoke_signed(
+      &ix,
+      &[
+        payer.clone(),
+        pool_acc.clone(),
+        lpt_acc.clone(),
+        mint_lpt_acc.clone(),
+        src_s_acc.clone(),
+        treasury_s_acc.clone(),
+        src_a_acc.clone(),
+        treasury_a_acc.clone(),
+        src_b_acc.clone(),
+        treasury_b_acc.clone(),
+        treasurer.clone(),
+        splt_program.clone(),
+        splata_program.clone(),
+        swap_program.clone(),
+        sysvar_rent_acc.clone(),
+        system_program.clone(),
+        config_acc.clone(),
+        router_program.clone(),
+      ],
+      seed,
+    )?;
+    Ok(())
+  }
+  ///
+  /// Add liquidity capped to the pool ratio
+  ///
+  pub fn add_liquidity_balanced<'a>(
+    delta_s: u64,
+    delta_a: u64,
+    delta_b: u64,
+    min_lpt: u64,
+    payer: &AccountInfo<'a>,
+    pool_acc: &AccountInfo<'a>,
+    lpt_acc: &AccountInfo<'a>,
+    mint_lpt_acc: &AccountInfo<'a>,
+    src_s_acc: &AccountInfo<'a>,
+    treasury_s_acc: &AccountInfo<'a>,
+    src_a_acc: &AccountInfo<'a>,
+    treasury_a_acc: &AccountInfo<'a>,
+    src_b_acc: &AccountInfo<'a>,
+    treasury_b_acc: &AccountInfo<'a>,
+    treasurer: &AccountInfo<'a>,
+    splt_program: &AccountInfo<'a>,
+    splata_program: &AccountInfo<'a>,
+    swap_program: &AccountInfo<'a>,
+    sysvar_rent_acc: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    config_acc: &AccountInfo<'a>,
+    router_program: &AccountInfo<'a>,
+    seed: &[&[&[u8]]],
+  ) -> ProgramResult {
+    let ix = IRouter::add_liquidity_balanced(
+      delta_s,
+      delta_a,
+      delta_b,
+      min_lpt,
+      *payer.key,
+      *pool_acc.key,
+      *lpt_acc.key,
+      *mint_lpt_acc.key,
+      *src_s_acc.key,
+      *treasury_s_acc.key,
+      *src_a_acc.key,
+      *treasury_a_acc.key,
+      *src_b_acc.key,
+      *treasury_b_acc.key,
+      *treasurer.key,
+      *splt_program.key,
+      *splata_program.key,
+      *swap_program.key,
+      *sysvar_rent_acc.key,
+      *system_program.key,
+      *config_acc.key,
+      *router_program.key,
+    )?;
+    invoke_signed(
+      &ix,
+      &[
+        payer.clone(),
+        pool_acc.clone(),
+        lpt_acc.clone(),
+        mint_lpt_acc.clone(),
+        src_s_acc.clone(),
+        treasury_s_acc.clone(),
+        src_a_acc.clone(),
+        treasury_a_acc.clone(),
+        src_b_acc.clone(),
+        treasury_b_acc.clone(),
+        treasurer.clone(),
+        splt_program.clone(),
+        splata_program.clone(),
+        swap_program.clone(),
+        sysvar_rent_acc.clone(),
+        system_program.clone(),
+        config_acc.clone(),
+        router_program.clone(),
+      ],
+      seed,
+    )?;
+    Ok(())
+  }
+  ///
+  /// Zap in
+  ///
+  pub fn zap_in<'a>(
+    amount: u64,
+    min_lpt: u64,
+    payer: &AccountInfo<'a>,
+    pool_acc: &AccountInfo<'a>,
+    vault_acc: &AccountInfo<'a>,
+    src_acc: &AccountInfo<'a>,
+    lpt_acc: &AccountInfo<'a>,
+    mint_lpt_acc: &AccountInfo<'a>,
+    src_s_acc: &AccountInfo<'a>,
+    mint_s_acc: &AccountInfo<'a>,
+    treasury_s_acc: &AccountInfo<'a>,
+    src_a_acc: &AccountInfo<'a>,
+    mint_a_acc: &AccountInfo<'a>,
+    treasury_a_acc: &AccountInfo<'a>,
+    src_b_acc: &AccountInfo<'a>,
+    mint_b_acc: &AccountInfo<'a>,
+    treasury_b_acc: &AccountInfo<'a>,
+    treasurer: &AccountInfo<'a>,
+    splt_program: &AccountInfo<'a>,
+    splata_program: &AccountInfo<'a>,
+    swap_program: &AccountInfo<'a>,
+    sysvar_rent_acc: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    config_acc: &AccountInfo<'a>,
+    router_program: &AccountInfo<'a>,
+    seed: &[&[&[u8]]],
+  ) -> ProgramResult {
+    let ix = IRouter::zap_in(
+      amount,
+      min_lpt,
+      *payer.key,
+      *pool_acc.key,
+      *vault_acc.key,
+      *src_acc.key,
+      *lpt_acc.key,
+      *mint_lpt_acc.key,
+      *src_s_acc.key,
+      *mint_s_acc.key,
+      *treasury_s_acc.key,
+      *src_a_acc.key,
+      *mint_a_acc.key,
+      *treasury_a_acc.key,
+      *src_b_acc.key,
+      *mint_b_acc.key,
+      *treasury_b_acc.key,
+      *treasurer.key,
+      *splt_program.key,
+      *splata_program.key,
+      *swap_program.key,
+      *sysvar_rent_acc.key,
+      *system_program.key,
+      *config_acc.key,
+      *router_program.key,
+    )?;
+    invoke_signed(
+      &ix,
+      &[
+        payer.clone(),
+        pool_acc.clone(),
+        vault_acc.clone(),
+        src_acc.clone(),
+        lpt_acc.clone(),
+        mint_lpt_acc.clone(),
+        src_s_acc.clone(),
+        mint_s_acc.clone(),
+        treasury_s_acc.clone(),
+        src_a_acc.clone(),
+        mint_a_acc.clone(),
+        treasury_a_acc.clone(),
+        src_b_acc.clone(),
+        mint_b_acc.clone(),
+        treasury_b_acc.clone(),
+        treasurer.clone(),
+        splt_program.clone(),
+        splata_program.clone(),
+        swap_program.clone(),
+        sysvar_rent_acc.clone(),
+        system_program.clone(),
+        config_acc.clone(),
+        router_program.clone(),
+      ],
+      seed,
+    )?;
+    Ok(())
+  }
+  ///
+  /// Zap out
+  ///
+  pub fn zap_out<'a>(
+    lpt: u64,
+    min_amount_out: u64,
+    payer: &AccountInfo<'a>,
+    pool_acc: &AccountInfo<'a>,
+    vault_acc: &AccountInfo<'a>,
+    lpt_acc: &AccountInfo<'a>,
+    mint_lpt_acc: &AccountInfo<'a>,
+    mint_ask_acc: &AccountInfo<'a>,
+    dst_s_acc: &AccountInfo<'a>,
+    mint_s_acc: &AccountInfo<'a>,
+    treasury_s_acc: &AccountInfo<'a>,
+    dst_a_acc: &AccountInfo<'a>,
+    mint_a_acc: &AccountInfo<'a>,
+    treasury_a_acc: &AccountInfo<'a>,
+    dst_b_acc: &AccountInfo<'a>,
+    mint_b_acc: &AccountInfo<'a>,
+    treasury_b_acc: &AccountInfo<'a>,
+    treasurer: &AccountInfo<'a>,
+    splt_program: &AccountInfo<'a>,
+    splata_program: &AccountInfo<'a>,
+    swap_program: &AccountInfo<'a>,
+    sysvar_rent_acc: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    config_acc: &AccountInfo<'a>,
+    router_program: &AccountInfo<'a>,
+    seed: &[&[&[u8]]],
+  ) -> ProgramResult {
+    let ix = IRouter::zap_out(
+      lpt,
+      min_amount_out,
+      *payer.key,
+      *pool_acc.key,
+      *vault_acc.key,
+      *lpt_acc.key,
+      *mint_lpt_acc.key,
+      *mint_ask_acc.key,
+      *dst_s_acc.key,
+      *mint_s_acc.key,
+      *treasury_s_acc.key,
+      *dst_a_acc.key,
+      *mint_a_acc.key,
+      *treasury_a_acc.key,
+      *dst_b_acc.key,
+      *mint_b_acc.key,
+      *treasury_b_acc.key,
+      *treasurer.key,
+      *splt_program.key,
+      *splata_program.key,
+      *swap_program.key,
+      *sysvar_rent_acc.key,
+      *system_program.key,
+      *config_acc.key,
+      *router_program.key,
+    )?;
+    invoke_signed(
+      &ix,
+      &[
+        payer.clone(),
+        pool_acc.clone(),
+        vault_acc.clone(),
+        lpt_acc.clone(),
+        mint_lpt_acc.clone(),
+        mint_ask_acc.clone(),
+        dst_s_acc.clone(),
+        mint_s_acc.clone(),
+        treasury_s_acc.clone(),
+        dst_a_acc.clone(),
+        mint_a_acc.clone(),
+        treasury_a_acc.clone(),
+        dst_b_acc.clone(),
+        mint_b_acc.clone(),
+        treasury_b_acc.clone(),
+        treasurer.clone(),
+        splt_program.clone(),
+        splata_program.clone(),
+        swap_program.clone(),
+        sysvar_rent_acc.clone(),
+        system_program.clone(),
+        config_acc.clone(),
+        router_program.clone(),
+      ],
+      seed,
+    )?;
+    Ok(())
+  }
+  ///
+  /// Migrate liquidity
+  ///
+  pub fn migrate_liquidity<'a>(
+    lpt: u64,
+    min_lpt: u64,
+    payer: &AccountInfo<'a>,
+    old_pool_acc: &AccountInfo<'a>,
+    old_vault_acc: &AccountInfo<'a>,
+    old_lpt_acc: &AccountInfo<'a>,
+    old_mint_lpt_acc: &AccountInfo<'a>,
+    old_treasurer: &AccountInfo<'a>,
+    old_s_acc: &AccountInfo<'a>,
+    old_mint_s_acc: &AccountInfo<'a>,
+    old_treasury_s_acc: &AccountInfo<'a>,
+    old_a_acc: &AccountInfo<'a>,
+    old_mint_a_acc: &AccountInfo<'a>,
+    old_treasury_a_acc: &AccountInfo<'a>,
+    old_b_acc: &AccountInfo<'a>,
+    old_mint_b_acc: &AccountInfo<'a>,
+    old_treasury_b_acc: &AccountInfo<'a>,
+    new_pool_acc: &AccountInfo<'a>,
+    new_lpt_acc: &AccountInfo<'a>,
+    new_mint_lpt_acc: &AccountInfo<'a>,
+    new_treasurer: &AccountInfo<'a>,
+    new_s_acc: &AccountInfo<'a>,
+    new_mint_s_acc: &AccountInfo<'a>,
+    new_treasury_s_acc: &AccountInfo<'a>,
+    new_a_acc: &AccountInfo<'a>,
+    new_mint_a_acc: &AccountInfo<'a>,
+    new_treasury_a_acc: &AccountInfo<'a>,
+    new_b_acc: &AccountInfo<'a>,
+    new_mint_b_acc: &AccountInfo<'a>,
+    new_treasury_b_acc: &AccountInfo<'a>,
+    splt_program: &AccountInfo<'a>,
+    splata_program: &AccountInfo<'a>,
+    swap_program: &AccountInfo<'a>,
+    sysvar_rent_acc: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    config_acc: &AccountInfo<'a>,
+    router_program: &AccountInfo<'a>,
+    seed: &[&[&[u8]]],
+  ) -> ProgramResult {
+    let ix = IRouter::migrate_liquidity(
+      lpt,
+      min_lpt,
+      *payer.key,
+      *old_pool_acc.key,
+      *old_vault_acc.key,
+      *old_lpt_acc.key,
+      *old_mint_lpt_acc.key,
+      *old_treasurer.key,
+      *old_s_acc.key,
+      *old_mint_s_acc.key,
+      *old_treasury_s_acc.key,
+      *old_a_acc.key,
+      *old_mint_a_acc.key,
+      *old_treasury_a_acc.key,
+      *old_b_acc.key,
+      *old_mint_b_acc.key,
+      *old_treasury_b_acc.key,
+      *new_pool_acc.key,
+      *new_lpt_acc.key,
+      *new_mint_lpt_acc.key,
+      *new_treasurer.key,
+      *new_s_acc.key,
+      *new_mint_s_acc.key,
+      *new_treasury_s_acc.key,
+      *new_a_acc.key,
+      *new_mint_a_acc.key,
+      *new_treasury_a_acc.key,
+      *new_b_acc.key,
+      *new_mint_b_acc.key,
+      *new_treasury_b_acc.key,
+      *splt_program.key,
+      *splata_program.key,
+      *swap_program.key,
+      *sysvar_rent_acc.key,
+      *system_program.key,
+      *config_acc.key,
+      *router_program.key,
+    )?;
+    invoke_signed(
+      &ix,
+      &[
+        payer.clone(),
+        old_pool_acc.clone(),
+        old_vault_acc.clone(),
+        old_lpt_acc.clone(),
+        old_mint_lpt_acc.clone(),
+        old_treasurer.clone(),
+        old_s_acc.clone(),
+        old_mint_s_acc.clone(),
+        old_treasury_s_acc.clone(),
+        old_a_acc.clone(),
+        old_mint_a_acc.clone(),
+        old_treasury_a_acc.clone(),
+        old_b_acc.clone(),
+        old_mint_b_acc.clone(),
+        old_treasury_b_acc.clone(),
+        new_pool_acc.clone(),
+        new_lpt_acc.clone(),
+        new_mint_lpt_acc.clone(),
+        new_treasurer.clone(),
+        new_s_acc.clone(),
+        new_mint_s_acc.clone(),
+        new_treasury_s_acc.clone(),
+        new_a_acc.clone(),
+        new_mint_a_acc.clone(),
+        new_treasury_a_acc.clone(),
+        new_b_acc.clone(),
+        new_mint_b_acc.clone(),
+        new_treasury_b_acc.clone(),
+        splt_program.clone(),
+        splata_program.clone(),
+        swap_program.clone(),
+        sysvar_rent_acc.clone(),
+        system_program.clone(),
+        config_acc.clone(),
+        router_program.clone(),
+      ],
+      seed,
+    )?;
+    Ok(())
+  }
+  ///
+  /// Remove liquidity
+  ///
+  pub fn remove_liquidity<'a>(
+    lpt: u64,
+    payer: &AccountInfo<'a>,
+    pool_acc: &AccountInfo<'a>,
+    lpt_acc: &AccountInfo<'a>,
+    mint_lpt_acc: &AccountInfo<'a>,
+    dst_s_acc: &AccountInfo<'a>,
+    mint_s_acc: &AccountInfo<'a>,
+    treasury_s_acc: &AccountInfo<'a>,
+    dst_a_acc: &AccountInfo<'a>,
+    mint_a_acc: &AccountInfo<'a>,
+    treasury_a_acc: &AccountInfo<'a>,
+    dst_b_acc: &AccountInfo<'a>,
+    mint_b_acc: &AccountInfo<'a>,
+    treasury_b_acc: &AccountInfo<'a>,
+    treasurer: &AccountInfo<'a>,
+    splt_program: &AccountInfo<'a>,
+    splata_program: &AccountInfo<'a>,
+    swap_program: &AccountInfo<'a>,
+    sysvar_rent_acc: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    config_acc: &AccountInfo<'a>,
+    router_program: &AccountInfo<'a>,
+    seed: &[&[&[u8]]],
+  ) -> ProgramResult {
+    let ix = IRouter::remove_liquidity(
+      lpt,
+      *payer.key,
+      *pool_acc.key,
+      *lpt_acc.key,
+      *mint_lpt_acc.key,
+      *dst_s_acc.key,
+      *mint_s_acc.key,
+      *treasury_s_acc.key,
+      *dst_a_acc.key,
+      *mint_a_acc.key,
+      *treasury_a_acc.key,
+      *dst_b_acc.key,
+      *mint_b_acc.key,
+      *treasury_b_acc.key,
+      *treasurer.key,
+      *splt_program.key,
+      *splata_program.key,
+      *swap_program.key,
+      *sysvar_rent_acc.key,
+      *system_program.key,
+      *config_acc.key,
+      *router_program.key,
+    )?;
+    invoke_signed(
+      &ix,
+      &[
+        payer.clone(),
+        pool_acc.clone(),
+        lpt_acc.clone(),
+        mint_lpt_acc.clone(),
+        dst_s_acc.clone(),
+        mint_s_acc.clone(),
+        treasury_s_acc.clone(),
+        dst_a_acc.clone(),
+        mint_a_acc.clone(),
+        treasury_a_acc.clone(),
+        dst_b_acc.clone(),
+        mint_b_acc.clone(),
+        treasury_b_acc.clone(),
+        treasurer.clone(),
+        splt_program.clone(),
+        splata_program.clone(),
+        swap_program.clone(),
+        sysvar_rent_acc.clone(),
+        system_program.clone(),
+        config_acc.clone(),
+        router_program.clone(),
+      ],
+      seed,
+    )?;
+    Ok(())
+  }
+  ///
+  /// Remove liquidity with minimum amounts out
+  ///
+  pub fn remove_liquidity_min_out<'a>(
+    lpt: u64,
+    min_s: u64,
+    min_a: u64,
+    min_b: u64,
+    payer: &AccountInfo<'a>,
+    pool_acc: &AccountInfo<'a>,
+    lpt_acc: &AccountInfo<'a>,
+    mint_lpt_acc: &AccountInfo<'a>,
+    dst_s_acc: &AccountInfo<'a>,
+    mint_s_acc: &AccountInfo<'a>,
+    treasury_s_acc: &AccountInfo<'a>,
+    dst_a_acc: &AccountInfo<'a>,
+    mint_a_acc: &AccountInfo<'a>,
+    treasury_a_acc: &AccountInfo<'a>,
+    dst_b_acc: &AccountInfo<'a>,
+    mint_b_acc: &AccountInfo<'a>,
+    treasury_b_acc: &AccountInfo<'a>,
+    treasurer: &AccountInfo<'a>,
+    splt_program: &AccountInfo<'a>,
+    splata_program: &AccountInfo<'a>,
+    swap_program: &AccountInfo<'a>,
+    sysvar_rent_acc: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    config_acc: &AccountInfo<'a>,
+    router_program: &AccountInfo<'a>,
+    seed: &[&[&[u8]]],
+  ) -> ProgramResult {
+    let ix = IRouter::remove_liquidity_min_out(
+      lpt,
+      min_s,
+      min_a,
+      min_b,
+      *payer.key,
+      *pool_acc.key,
+      *lpt_acc.key,
+      *mint_lpt_acc.key,
+      *dst_s_acc.key,
+      *mint_s_acc.key,
+      *treasury_s_acc.key,
+      *dst_a_acc.key,
+      *mint_a_acc.key,
+      *treasury_a_acc.key,
+      *dst_b_acc.key,
+      *mint_b_acc.key,
+      *treasury_b_acc.key,
+      *treasurer.key,
+      *splt_program.key,
+      *splata_program.key,
+      *swap_program.key,
+      *sysvar_rent_acc.key,
+      *system_program.key,
+      *config_acc.key,
+      *router_program.key,
+    )?;
+    invoke_signed(
+      &ix,
+      &[
+        payer.clone(),
+        pool_acc.clone(),
+        lpt_acc.clone(),
+        mint_lpt_acc.clone(),
+        dst_s_acc.clone(),
+        mint_s_acc.clone(),
+        treasury_s_acc.clone(),
+        dst_a_acc.clone(),
+        mint_a_acc.clone(),
+        treasury_a_acc.clone(),
+        dst_b_acc.clone(),
+        mint_b_acc.clone(),
+        treasury_b_acc.clone(),
+        treasurer.clone(),
+        splt_program.clone(),
+        splata_program.clone(),
+        swap_program.clone(),
+        sysvar_rent_acc.clone(),
+        system_program.clone(),
+        config_acc.clone(),
+        router_program.clone(),
+      ],
+      seed,
+    )?;
+    Ok(())
+  }
+  ///
+  /// Multi-hop route
+  ///
+  pub fn multi_route<'a>(
+    amount: u64,
+    limits: Vec<u64>,
+    flags: u8,
+    payer: &AccountInfo<'a>,
+    src_acc: &AccountInfo<'a>,
+    hops: &[Hop<'_, 'a>],
+    splt_program: &AccountInfo<'a>,
+    splata_program: &AccountInfo<'a>,
+    swap_program: &AccountInfo<'a>,
+    sysvar_rent_acc: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    config_acc: &AccountInfo<'a>,
+    router_program: &AccountInfo<'a>,
+    seed: &[&[&[u8]]],
+  ) -> ProgramResult {
+    let ix = IRouter::multi_route(
+      amount,
+      limits,
+      flags,
+      *payer.key,
+      *src_acc.key,
+      &Self::hop_keys(hops),
+      *splt_program.key,
+      *splata_program.key,
+      *swap_program.key,
+      *sysvar_rent_acc.key,
+      *system_program.key,
+      *config_acc.key,
+      *router_program.key,
+    )?;
+    invoke_signed(
+      &ix,
+      &Self::route_account_infos(
+        payer,
+        src_acc,
+        hops,
+        splt_program,
+        splata_program,
+        swap_program,
+        sysvar_rent_acc,
+        system_program,
+        config_acc,
+        router_program,
+      ),
+      seed,
+    )?;
+    Ok(())
+  }
+  ///
+  /// Multi-hop route with a minimum final output
+  ///
+  pub fn route_min_out<'a>(
+    amount: u64,
+    min_amount_out: u64,
+    flags: u8,
+    payer: &AccountInfo<'a>,
+    src_acc: &AccountInfo<'a>,
+    hops: &[Hop<'_, 'a>],
+    splt_program: &AccountInfo<'a>,
+    splata_program: &AccountInfo<'a>,
+    swap_program: &AccountInfo<'a>,
+    sysvar_rent_acc: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    config_acc: &AccountInfo<'a>,
+    router_program: &AccountInfo<'a>,
+    seed: &[&[&[u8]]],
+  ) -> ProgramResult {
+    let ix = IRouter::route_min_out(
+      amount,
+      min_amount_out,
+      flags,
+      *payer.key,
+      *src_acc.key,
+      &Self::hop_keys(hops),
+      *splt_program.key,
+      *splata_program.key,
+      *swap_program.key,
+      *sysvar_rent_acc.key,
+      *system_program.key,
+      *config_acc.key,
+      *router_program.key,
+    )?;
+    invoke_signed(
+      &ix,
+      &Self::route_account_infos(
+        payer,
+        src_acc,
+        hops,
+        splt_program,
+        splata_program,
+        swap_program,
+        sysvar_rent_acc,
+        system_program,
+        config_acc,
+        router_program,
+      ),
+      seed,
+    )?;
+    Ok(())
+  }
+  ///
+  /// Multi-hop route for an exact final output
+  ///
+  pub fn route_exact_out<'a>(
+    amount_out: u64,
+    max_amount_in: u64,
+    flags: u8,
+    payer: &AccountInfo<'a>,
+    src_acc: &AccountInfo<'a>,
+    hops: &[Hop<'_, 'a>],
+    splt_program: &AccountInfo<'a>,
+    splata_program: &AccountInfo<'a>,
+    swap_program: &AccountInfo<'a>,
+    sysvar_rent_acc: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    config_acc: &AccountInfo<'a>,
+    router_program: &AccountInfo<'a>,
+    seed: &[&[&[u8]]],
+  ) -> ProgramResult {
+    let ix = IRouter::route_exact_out(
+      amount_out,
+      max_amount_in,
+      flags,
+      *payer.key,
+      *src_acc.key,
+      &Self::hop_keys(hops),
+      *splt_program.key,
+      *splata_program.key,
+      *swap_program.key,
+      *sysvar_rent_acc.key,
+      *system_program.key,
+      *config_acc.key,
+      *router_program.key,
+    )?;
+    invoke_signed(
+      &ix,
+      &Self::route_account_infos(
+        payer,
+        src_acc,
+        hops,
+        splt_program,
+        splata_program,
+        swap_program,
+        sysvar_rent_acc,
+        system_program,
+        config_acc,
+        router_program,
+      ),
+      seed,
+    )?;
+    Ok(())
+  }
+  ///
+  /// Route through a bridge pool between two primary pools
+  ///
+  pub fn bridge_route<'a>(
+    amount: u64,
+    first_limit: u64,
+    bridge_limit: u64,
+    second_limit: u64,
+    flags: u8,
+    payer: &AccountInfo<'a>,
+    src_acc: &AccountInfo<'a>,
+    hops: &[Hop<'_, 'a>; 3],
+    splt_program: &AccountInfo<'a>,
+    splata_program: &AccountInfo<'a>,
+    swap_program: &AccountInfo<'a>,
+    sysvar_rent_acc: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    config_acc: &AccountInfo<'a>,
+    router_program: &AccountInfo<'a>,
+    seed: &[&[&[u8]]],
+  ) -> ProgramResult {
+    let hop_keys = Self::hop_keys(hops);
+    let ix = IRouter::bridge_route(
+      amount,
+      first_limit,
+      bridge_limit,
+      second_limit,
+      flags,
+      *payer.key,
+      *src_acc.key,
+      hop_keys[0],
+      hop_keys[1],
+      hop_keys[2],
+      *splt_program.key,
+      *splata_program.key,
+      *swap_program.key,
+      *sysvar_rent_acc.key,
+      *system_program.key,
+      *config_acc.key,
+      *router_program.key,
+    )?;
+    invoke_signed(
+      &ix,
+      &Self::route_account_infos(
+        payer,
+        src_acc,
+        hops,
+        splt_program,
+        splata_program,
+        swap_program,
+        sysvar_rent_acc,
+        system_program,
+        config_acc,
+        router_program,
+      ),
+      seed,
+    )?;
+    Ok(())
+  }
+  ///
+  /// Split route over several paths to the same destination
+  ///
+  pub fn split_route<'a>(
+    min_amount_out: u64,
+    paths: &[(u64, &[Hop<'_, 'a>])],
+    flags: u8,
+    payer: &AccountInfo<'a>,
+    src_acc: &AccountInfo<'a>,
+    dst_acc: &AccountInfo<'a>,
+    splt_program: &AccountInfo<'a>,
+    splata_program: &AccountInfo<'a>,
+    swap_program: &AccountInfo<'a>,
+    sysvar_rent_acc: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    config_acc: &AccountInfo<'a>,
+    router_program: &AccountInfo<'a>,
+    seed: &[&[&[u8]]],
+  ) -> ProgramResult {
+    let hop_keys = paths
+      .iter()
+      .map(|(_, hops)| Self::hop_keys(hops))
+      .collect::<Vec<Vec<IHop>>>();
+    let ix = IRouter::split_route(
+      min_amount_out,
+      &paths
+        .iter()
+        .zip(hop_keys.iter())
+        .map(|((amount, _), hop_keys)| (*amount, &hop_keys[..]))
+        .collect::<Vec<(u64, &[IHop])>>(),
+      flags,
+      *payer.key,
+      *src_acc.key,
+      *dst_acc.key,
+      *splt_program.key,
+      *splata_program.key,
+      *swap_program.key,
+      *sysvar_rent_acc.key,
+      *system_program.key,
+      *config_acc.key,
+      *router_program.key,
+    )?;
+    let mut account_infos =
+      Vec::with_capacity(10 + 8 * hop_keys.iter().map(Vec::len).sum::<usize>());
+    account_infos.push(payer.clone());
+    account_infos.push(src_acc.clone());
+    account_infos.push(dst_acc.clone());
+    account_infos.push(splt_program.clone());
+    account_infos.push(splata_program.clone());
+    account_infos.push(swap_program.clone());
+    account_infos.push(sysvar_rent_acc.clone());
+    account_infos.push(system_program.clone());
+    account_infos.push(config_acc.clone());
+    for (_, hops) in paths {
+      Self::push_hop_infos(hops, &mut account_infos);
+    }
+    account_infos.push(router_program.clone());
+    invoke_signed(&ix, &account_infos, seed)?;
+    Ok(())
+  }
+  ///
+  /// Smart swap over the best of the candidate pools
+  ///
+  pub fn smart_swap<'a>(
+    amount: u64,
+    min_amount_out: u64,
+    flags: u8,
+    payer: &AccountInfo<'a>,
+    src_acc: &AccountInfo<'a>,
+    dst_acc: &AccountInfo<'a>,
+    mint_ask_acc: &AccountInfo<'a>,
+    sen_acc: &AccountInfo<'a>,
+    mint_sen_acc: &AccountInfo<'a>,
+    candidates: &[Candidate<'_, 'a>],
+    splt_program: &AccountInfo<'a>,
+    splata_program: &AccountInfo<'a>,
+    swap_program: &AccountInfo<'a>,
+    sysvar_rent_acc: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    config_acc: &AccountInfo<'a>,
+    router_program: &AccountInfo<'a>,
+    seed: &[&[&[u8]]],
+  ) -> ProgramResult {
+    let ix = IRouter::smart_swap(
+      amount,
+      min_amount_out,
+      flags,
+      *payer.key,
+      *src_acc.key,
+      *dst_acc.key,
+      *mint_ask_acc.key,
+      *sen_acc.key,
+      *mint_sen_acc.key,
+      &candidates
+        .iter()
+        .map(|candidate| ICandidate {
+          pool_acc: *candidate.pool_acc.key,
+          vault_acc: *candidate.vault_acc.key,
+          treasury_s_acc: *candidate.treasury_s_acc.key,
+          treasury_a_acc: *candidate.treasury_a_acc.key,
+          treasury_b_acc: *candidate.treasury_b_acc.key,
+          treasurer: *candidate.treasurer.key,
+        })
+        .collect::<Vec<ICandidate>>(),
+      *splt_program.key,
+      *splata_program.key,
+      *swap_program.key,
+      *sysvar_rent_acc.key,
+      *system_program.key,
+      *config_acc.key,
+      *router_program.key,
+    )?;
+    let mut account_infos = Vec::with_capacity(13 + 6 * candidates.len());
+    account_infos.push(payer.clone());
+    account_infos.push(src_acc.clone());
+    account_infos.push(dst_acc.clone());
+    account_infos.push(mint_ask_acc.clone());
+    account_infos.push(sen_acc.clone());
+    account_infos.push(mint_sen_acc.clone());
+    account_infos.push(splt_program.clone());
+    account_infos.push(splata_program.clone());
+    account_infos.push(swap_program.clone());
+    account_infos.push(sysvar_rent_acc.clone());
+    account_infos.push(system_program.clone());
+    account_infos.push(config_acc.clone());
+    for candidate in candidates {
+      account_infos.push(candidate.pool_acc.clone());
+      account_infos.push(candidate.vault_acc.clone());
+      account_infos.push(candidate.treasury_s_acc.clone());
+      account_infos.push(candidate.treasury_a_acc.clone());
+      account_infos.push(candidate.treasury_b_acc.clone());
+      account_infos.push(candidate.treasurer.clone());
+    }
+    account_infos.push(router_program.clone());
+    invoke_signed(&ix, &account_infos, seed)?;
+    Ok(())
+  }
+  ///
+  /// Quote a multi-hop route, each hop being its pool and its ask mint
+  ///
+  pub fn quote<'a>(
+    amount: u64,
+    mint_bid_acc: &AccountInfo<'a>,
+    hops: &[(&AccountInfo<'a>, &AccountInfo<'a>)],
+    swap_program: &AccountInfo<'a>,
+    config_acc: &AccountInfo<'a>,
+    router_program: &AccountInfo<'a>,
+  ) -> ProgramResult {
+    let ix = IRouter::quote(
+      amount,
+      *mint_bid_acc.key,
+      &hops
+        .iter()
+        .map(|(pool_acc, mint_ask_acc)| (*pool_acc.key, *mint_ask_acc.key))
+        .collect::<Vec<_>>(),
+      *swap_program.key,
+      *config_acc.key,
+      *router_program.key,
+    )?;
+    let mut account_infos = Vec::with_capacity(4 + 2 * hops.len());
+    account_infos.push(mint_bid_acc.clone());
+    account_infos.push(swap_program.clone());
+    account_infos.push(config_acc.clone());
+    for (pool_acc, mint_ask_acc) in hops {
+      account_infos.push((*pool_acc).clone());
+      account_infos.push((*mint_ask_acc).clone());
+    }
+    account_infos.push(router_program.clone());
+    invoke(&ix, &account_infos)?;
+    Ok(())
+  }
+
+  // Return data of the last router call as little-endian u64 words
+  fn return_words(router_program: &AccountInfo) -> Option<Vec<u64>> {
+    let (program_id, data) = get_return_data()?;
+    if program_id != *router_program.key || data.len() % 8 != 0 {
+      return None;
+    }
+    data
+      .chunks(8)
+      .map(|slice| slice.try_into().ok().map(u64::from_le_bytes))
+      .collect()
+  }
+
+  fn hop_keys(hops: &[Hop]) -> Vec<IHop> {
+    hops
+      .iter()
+      .map(|hop| IHop {
+        pool_acc: *hop.pool_acc.key,
+        vault_acc: *hop.vault_acc.key,
+        treasury_bid_acc: *hop.treasury_bid_acc.key,
+        dst_acc: *hop.dst_acc.key,
+        mint_ask_acc: *hop.mint_ask_acc.key,
+        treasury_ask_acc: *hop.treasury_ask_acc.key,
+        treasury_sen_acc: *hop.treasury_sen_acc.key,
+        treasurer: *hop.treasurer.key,
+      })
+      .collect()
+  }
+
+  fn route_account_infos<'a>(
+    payer: &AccountInfo<'a>,
+    src_acc: &AccountInfo<'a>,
+    hops: &[Hop<'_, 'a>],
+    splt_program: &AccountInfo<'a>,
+    splata_program: &AccountInfo<'a>,
+    swap_program: &AccountInfo<'a>,
+    sysvar_rent_acc: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    config_acc: &AccountInfo<'a>,
+    router_program: &AccountInfo<'a>,
+  ) -> Vec<AccountInfo<'a>> {
+    let mut account_infos = Vec::with_capacity(9 + 8 * hops.len());
+    account_infos.push(payer.clone());
+    account_infos.push(src_acc.clone());
+    account_infos.push(splt_program.clone());
+    account_infos.push(splata_program.clone());
+    account_infos.push(swap_program.clone());
+    account_infos.push(sysvar_rent_acc.clone());
+    account_infos.push(system_program.clone());
+    account_infos.push(config_acc.clone());
+    Self::push_hop_infos(hops, &mut account_infos);
+    account_infos.push(router_program.clone());
+    account_infos
+  }
+
+  fn push_hop_infos<'a>(hops: &[Hop<'_, 'a>], account_infos: &mut Vec<AccountInfo<'a>>) {
+    for hop in hops {
+      account_infos.push(hop.pool_acc.clone());
+      account_infos.push(hop.vault_acc.clone());
+      account_infos.push(hop.treasury_bid_acc.clone());
+      account_infos.push(hop.dst_acc.clone());
+      account_infos.push(hop.mint_ask_acc.clone());
+      account_infos.push(hop.treasury_ask_acc.clone());
+      account_infos.push(hop.treasury_sen_acc.clone());
+      account_infos.push(hop.treasurer.clone());
+    }
+  }
+}
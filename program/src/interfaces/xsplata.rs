@@ -5,6 +5,7 @@ use solana_program::{
 
 pub struct XSPLATA {}
 
+#[allow(clippy::too_many_arguments)]
 impl XSPLATA {
   ///
   /// Initialize account
@@ -0,0 +1,44 @@
+use crate::interfaces::isplt::ISPLT;
+use solana_program::{
+  account_info::AccountInfo, entrypoint::ProgramResult, program::invoke_signed,
+};
+
+pub struct XSPLT {}
+
+impl XSPLT {
+  ///
+  /// Close account
+  ///
+  pub fn close_account<'a>(
+    target_acc: &AccountInfo<'a>,
+    dst_acc: &AccountInfo<'a>,
+    owner: &AccountInfo<'a>,
+    splt_program: &AccountInfo<'a>,
+    seed: &[&[&[u8]]],
+  ) -> ProgramResult {
+    let ix = ISPLT::close_account(*target_acc.key, *dst_acc.key, *owner.key, *splt_program.key)?;
+    invoke_signed(
+      &ix,
+      &[
+        target_acc.clone(),
+        dst_acc.clone(),
+        owner.clone(),
+        splt_program.clone(),
+      ],
+      seed,
+    )?;
+    Ok(())
+  }
+  ///
+  /// Sync native
+  ///
+  pub fn sync_native<'a>(
+    target_acc: &AccountInfo<'a>,
+    splt_program: &AccountInfo<'a>,
+    seed: &[&[&[u8]]],
+  ) -> ProgramResult {
+    let ix = ISPLT::sync_native(*target_acc.key, *splt_program.key)?;
+    invoke_signed(&ix, &[target_acc.clone(), splt_program.clone()], seed)?;
+    Ok(())
+  }
+}
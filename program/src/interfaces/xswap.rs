@@ -5,6 +5,7 @@ use solana_program::{
 
 pub struct XSwap {}
 
+#[allow(clippy::too_many_arguments)]
 impl XSwap {
   ///
   /// Add liquidity
@@ -1,5 +1,8 @@
+#[cfg(feature = "client")]
+pub mod client;
 pub mod entrypoint;
 pub mod error;
+pub mod event;
 pub mod helper;
 pub mod instruction;
 pub mod interfaces;
@@ -1,28 +1,194 @@
 use crate::error::AppError;
+use crate::event::{Event, EventData, Fill};
 use crate::helper::oracle::Oracle;
 use crate::instruction::AppInstruction;
-use crate::interfaces::{xsplata::XSPLATA, xswap::XSwap};
-use crate::schema::{account::Account, pool::Pool};
+use crate::interfaces::{xsplata::XSPLATA, xsplt::XSPLT, xswap::XSwap};
+use crate::schema::{
+  account::{native_mint, Account},
+  config::Config,
+  pool::Pool,
+};
 use solana_program::{
   account_info::{next_account_info, AccountInfo},
+  bpf_loader_upgradeable,
   entrypoint::ProgramResult,
   msg,
+  program::{invoke_signed, set_return_data},
   program_error::ProgramError,
   program_pack::{IsInitialized, Pack},
   pubkey::Pubkey,
+  rent::Rent,
+  system_instruction,
+  sysvar::Sysvar,
 };
+use std::convert::TryInto;
+
+///
+/// Accounts of a single hop in a multi-hop route
+///
+pub struct Hop<'b, 'a> {
+  pub pool_acc: &'b AccountInfo<'a>,
+  pub vault_acc: &'b AccountInfo<'a>,
+  pub treasury_bid_acc: &'b AccountInfo<'a>,
+  pub dst_acc: &'b AccountInfo<'a>,
+  pub mint_ask_acc: &'b AccountInfo<'a>,
+  pub treasury_ask_acc: &'b AccountInfo<'a>,
+  pub treasury_sen_acc: &'b AccountInfo<'a>,
+  pub treasurer: &'b AccountInfo<'a>,
+}
+
+impl<'b, 'a> Hop<'b, 'a> {
+  pub fn next<I: Iterator<Item = &'b AccountInfo<'a>>>(iter: &mut I) -> Result<Self, ProgramError> {
+    Ok(Hop {
+      pool_acc: next_account_info(iter)?,
+      vault_acc: next_account_info(iter)?,
+      treasury_bid_acc: next_account_info(iter)?,
+      dst_acc: next_account_info(iter)?,
+      mint_ask_acc: next_account_info(iter)?,
+      treasury_ask_acc: next_account_info(iter)?,
+      treasury_sen_acc: next_account_info(iter)?,
+      treasurer: next_account_info(iter)?,
+    })
+  }
+}
+
+///
+/// Accounts of a candidate pool for on-chain path selection
+///
+pub struct Candidate<'b, 'a> {
+  pub pool_acc: &'b AccountInfo<'a>,
+  pub vault_acc: &'b AccountInfo<'a>,
+  pub treasury_s_acc: &'b AccountInfo<'a>,
+  pub treasury_a_acc: &'b AccountInfo<'a>,
+  pub treasury_b_acc: &'b AccountInfo<'a>,
+  pub treasurer: &'b AccountInfo<'a>,
+  pub pool_data: Pool,
+  pub is_frozen: bool,
+}
+
+impl<'b, 'a> Candidate<'b, 'a> {
+  pub fn next<I: Iterator<Item = &'b AccountInfo<'a>>>(
+    iter: &mut I,
+    swap_program: &AccountInfo,
+  ) -> Result<Self, ProgramError> {
+    let candidate = Candidate {
+      pool_acc: next_account_info(iter)?,
+      vault_acc: next_account_info(iter)?,
+      treasury_s_acc: next_account_info(iter)?,
+      treasury_a_acc: next_account_info(iter)?,
+      treasury_b_acc: next_account_info(iter)?,
+      treasurer: next_account_info(iter)?,
+      pool_data: Pool::default(),
+      is_frozen: false,
+    };
+    // Cross-check the accounts against the pool
+    let pool_data = Processor::unpack_pool(candidate.pool_acc, swap_program)?;
+    Processor::is_vault(&pool_data, candidate.vault_acc)?;
+    Processor::is_treasury(&pool_data, candidate.treasury_s_acc, pool_data.mint_s)?;
+    Processor::is_treasury(&pool_data, candidate.treasury_a_acc, pool_data.mint_a)?;
+    Processor::is_treasury(&pool_data, candidate.treasury_b_acc, pool_data.mint_b)?;
+    // A frozen candidate is skipped rather than failing the whole swap
+    let mut is_frozen = pool_data.is_frozen();
+    for acc in [
+      candidate.treasury_s_acc,
+      candidate.treasury_a_acc,
+      candidate.treasury_b_acc,
+    ]
+    .iter()
+    {
+      is_frozen = is_frozen || Processor::is_frozen_acc(acc)?;
+    }
+    Ok(Candidate {
+      pool_data,
+      is_frozen,
+      ..candidate
+    })
+  }
+
+  // Find the treasury account that holds the mint in this pool
+  pub fn treasury(&self, mint: Pubkey) -> Option<&'b AccountInfo<'a>> {
+    let treasury = if self.pool_data.mint_s == mint {
+      self.pool_data.treasury_s
+    } else if self.pool_data.mint_a == mint {
+      self.pool_data.treasury_a
+    } else if self.pool_data.mint_b == mint {
+      self.pool_data.treasury_b
+    } else {
+      return None;
+    };
+    [
+      self.treasury_s_acc,
+      self.treasury_a_acc,
+      self.treasury_b_acc,
+    ]
+    .iter()
+    .find(|acc| *acc.key == treasury)
+    .copied()
+  }
+
+  pub fn quote(&self, bid_mint: Pubkey, ask_mint: Pubkey, amount: u64) -> Option<u64> {
+    self.treasury(bid_mint)?;
+    self.treasury(ask_mint)?;
+    Processor::estimate_ask_amount(&self.pool_data, bid_mint, ask_mint, amount).ok()
+  }
+
+  #[allow(clippy::too_many_arguments)]
+  pub fn swap(
+    &self,
+    amount: u64,
+    limit: u64,
+    payer: &'b AccountInfo<'a>,
+    src_acc: &'b AccountInfo<'a>,
+    dst_acc: &'b AccountInfo<'a>,
+    bid_mint: Pubkey,
+    ask_mint: Pubkey,
+    splt_program: &'b AccountInfo<'a>,
+    swap_program: &'b AccountInfo<'a>,
+  ) -> Result<Fill, ProgramError> {
+    let treasury_bid_acc = self
+      .treasury(bid_mint)
+      .ok_or(AppError::CannotFindReserves)?;
+    let treasury_ask_acc = self
+      .treasury(ask_mint)
+      .ok_or(AppError::CannotFindReserves)?;
+    let treasury_sen_acc = self
+      .treasury(self.pool_data.mint_s)
+      .ok_or(AppError::CannotFindReserves)?;
+    Processor::swap_with_fill(
+      amount,
+      limit,
+      payer,
+      self.pool_acc,
+      self.vault_acc,
+      src_acc,
+      treasury_bid_acc,
+      dst_acc,
+      treasury_ask_acc,
+      treasury_sen_acc,
+      self.treasurer,
+      splt_program,
+      swap_program,
+    )
+  }
+}
 
 pub struct Processor {}
 
 impl Processor {
   pub fn process(
-    _program_id: &Pubkey,
+    program_id: &Pubkey,
     accounts: &[AccountInfo],
     instruction_data: &[u8],
   ) -> ProgramResult {
     let instruction = AppInstruction::unpack(instruction_data)?;
+    // Events are tagged with the instruction they come from
+    let kind = instruction_data[0];
     match instruction {
-      AppInstruction::Swap { amount, limit } => {
+      AppInstruction::Swap {
+        amount,
+        limit,
+        flags,
+      } => {
         msg!("Calling Swap function");
         let accounts_iter = &mut accounts.iter();
         let payer = next_account_info(accounts_iter)?;
@@ -40,9 +206,36 @@ impl Processor {
         let swap_program = next_account_info(accounts_iter)?;
         let sysvar_rent_acc = next_account_info(accounts_iter)?;
         let system_program = next_account_info(accounts_iter)?;
+        let config_acc = next_account_info(accounts_iter)?;
+        // Wrapping needs the native mint to create the source account
+        let native_mint_acc = match flags & AppInstruction::WRAP_BID {
+          0 => None,
+          _ => Some(next_account_info(accounts_iter)?),
+        };
+
+        let config_data = Self::read_config(program_id, config_acc)?;
+        Self::is_unpaused(&config_data, Config::PAUSE_SWAP)?;
+        Self::is_trusted(&config_data, swap_program, splt_program, splata_program)?;
+        // Cross-check the accounts against the pool
+        let pool_data = Self::read_pool(pool_acc, swap_program)?;
+        let bid_mint = match native_mint_acc {
+          Some(native_mint_acc) => *native_mint_acc.key,
+          None => Account::unpack(&src_acc.data.borrow())?.mint,
+        };
+        Self::is_vault(&pool_data, vault_acc)?;
+        Self::is_treasury(&pool_data, treasury_bid_acc, bid_mint)?;
+        Self::is_treasury(&pool_data, treasury_ask_acc, *mint_bid_acc.key)?;
+        Self::is_treasury(&pool_data, treasury_sen_acc, pool_data.mint_s)?;
+        Self::is_unfrozen(&[
+          src_acc,
+          treasury_bid_acc,
+          dst_acc,
+          treasury_ask_acc,
+          treasury_sen_acc,
+        ])?;
 
         // Initialize destination account just in case
-        if !Self::is_rented_and_initialized_acc(&dst_acc)? {
+        if !Self::is_rented_and_initialized_acc(dst_acc)? {
           XSPLATA::initialize_account(
             payer,
             dst_acc,
@@ -55,8 +248,22 @@ impl Processor {
             &[],
           )?;
         }
+        // Wrap SOL into the source account just in case
+        let mut is_temporary = false;
+        if let Some(native_mint_acc) = native_mint_acc {
+          is_temporary = Self::wrap_sol(
+            amount,
+            payer,
+            src_acc,
+            native_mint_acc,
+            system_program,
+            splt_program,
+            sysvar_rent_acc,
+            splata_program,
+          )?;
+        }
         // Swap
-        XSwap::swap(
+        let fill = Self::swap_with_fill(
           amount,
           limit,
           payer,
@@ -70,8 +277,21 @@ impl Processor {
           treasurer,
           splt_program,
           swap_program,
-          &[],
         )?;
+        // Close the temporary source and unwrap the destination just in case
+        if is_temporary {
+          XSPLT::close_account(src_acc, payer, payer, splt_program, &[])?;
+        }
+        if flags & AppInstruction::UNWRAP_ASK != 0 {
+          Self::unwrap_sol(payer, dst_acc, splt_program)?;
+        }
+        Self::return_fills(fill.amount_out, &[fill]);
+        Event {
+          kind,
+          authority: *payer.key,
+          data: EventData::Swap { fills: vec![fill] },
+        }
+        .emit();
 
         Ok(())
       }
@@ -80,6 +300,7 @@ impl Processor {
         amount,
         first_limit,
         second_limit,
+        flags,
       } => {
         msg!("Calling Route function");
         let accounts_iter = &mut accounts.iter();
@@ -108,14 +329,46 @@ impl Processor {
         let swap_program = next_account_info(accounts_iter)?;
         let sysvar_rent_acc = next_account_info(accounts_iter)?;
         let system_program = next_account_info(accounts_iter)?;
+        let config_acc = next_account_info(accounts_iter)?;
+
+        let config_data = Self::read_config(program_id, config_acc)?;
+        Self::is_unpaused(&config_data, Config::PAUSE_ROUTE)?;
+        Self::is_trusted(&config_data, swap_program, splt_program, splata_program)?;
 
-        let first_pool_data = Pool::unpack(&first_pool_acc.data.borrow())?;
-        let second_pool_data = Pool::unpack(&second_pool_acc.data.borrow())?;
-        if first_pool_data.mint_s != second_pool_data.mint_s {
+        let first_pool_data = Self::read_pool(first_pool_acc, swap_program)?;
+        let second_pool_data = Self::read_pool(second_pool_acc, swap_program)?;
+        if first_pool_data.mint_s != second_pool_data.mint_s
+          || first_pool_data.mint_s != *mint_sen_acc.key
+        {
           return Err(AppError::UnmatchedPrimaryMints.into());
         }
+        // Cross-check the accounts against the pools
+        Self::is_vault(&first_pool_data, first_vault_acc)?;
+        Self::is_treasury(&first_pool_data, treasury_bid_acc, *mint_bid_acc.key)?;
+        Self::is_treasury(
+          &first_pool_data,
+          first_treasury_sen_acc,
+          first_pool_data.mint_s,
+        )?;
+        Self::is_vault(&second_pool_data, second_vault_acc)?;
+        Self::is_treasury(&second_pool_data, treasury_ask_acc, *mint_ask_acc.key)?;
+        Self::is_treasury(
+          &second_pool_data,
+          second_treasury_sen_acc,
+          second_pool_data.mint_s,
+        )?;
+        Self::is_unfrozen(&[
+          src_acc,
+          treasury_bid_acc,
+          first_treasury_sen_acc,
+          sen_acc,
+          dst_acc,
+          treasury_ask_acc,
+          second_treasury_sen_acc,
+        ])?;
         // Initialize middle account just in case (usually being SEN)
-        if !Self::is_rented_and_initialized_acc(&sen_acc)? {
+        let is_temporary_middle = !Self::is_rented_and_initialized_acc(sen_acc)?;
+        if is_temporary_middle {
           XSPLATA::initialize_account(
             payer,
             sen_acc,
@@ -129,7 +382,7 @@ impl Processor {
           )?;
         }
         // Initialize end account just in case
-        if !Self::is_rented_and_initialized_acc(&dst_acc)? {
+        if !Self::is_rented_and_initialized_acc(dst_acc)? {
           XSPLATA::initialize_account(
             payer,
             dst_acc,
@@ -142,24 +395,22 @@ impl Processor {
             &[],
           )?;
         }
-        // Estimate middle amount
-        let bid_reserve = Self::parse_reserve(&first_pool_data, *mint_bid_acc.key)
-          .ok_or(AppError::CannotFindReserves)?;
-        let middle_reserve = Self::parse_reserve(&first_pool_data, *mint_sen_acc.key)
-          .ok_or(AppError::CannotFindReserves)?;
-        let new_bid_reserve = bid_reserve.checked_add(amount).ok_or(AppError::Overflow)?;
-        let (new_middle_reserve, _, _) = Oracle::curve_in_fee(
-          new_bid_reserve,
-          bid_reserve,
-          first_pool_data.reserve_s,
-          true,
-        )
-        .ok_or(AppError::Overflow)?;
-        let middle_amount = middle_reserve
-          .checked_sub(new_middle_reserve)
-          .ok_or(AppError::Overflow)?;
+        // Wrap SOL into the source account just in case
+        let mut is_temporary = false;
+        if flags & AppInstruction::WRAP_BID != 0 {
+          is_temporary = Self::wrap_sol(
+            amount,
+            payer,
+            src_acc,
+            mint_bid_acc,
+            system_program,
+            splt_program,
+            sysvar_rent_acc,
+            splata_program,
+          )?;
+        }
         // Routing #1
-        XSwap::swap(
+        let first_fill = Self::swap_with_fill(
           amount,
           first_limit,
           payer,
@@ -173,11 +424,10 @@ impl Processor {
           first_treasurer,
           splt_program,
           swap_program,
-          &[],
         )?;
-        // Routing #2
-        XSwap::swap(
-          middle_amount,
+        // Routing #2 swaps exactly what the first hop delivered
+        let second_fill = Self::swap_with_fill(
+          first_fill.amount_out,
           second_limit,
           payer,
           second_pool_acc,
@@ -190,8 +440,27 @@ impl Processor {
           second_treasurer,
           splt_program,
           swap_program,
-          &[],
         )?;
+        // Close the temporary source and unwrap the destination just in case
+        if is_temporary {
+          XSPLT::close_account(src_acc, payer, payer, splt_program, &[])?;
+        }
+        if flags & AppInstruction::UNWRAP_ASK != 0 {
+          Self::unwrap_sol(payer, dst_acc, splt_program)?;
+        }
+        // Refund the rent of the middle account if the route created it
+        if is_temporary_middle {
+          Self::close_temporary(flags, &[sen_acc], payer, splt_program)?;
+        }
+        Self::return_fills(second_fill.amount_out, &[first_fill, second_fill]);
+        Event {
+          kind,
+          authority: *payer.key,
+          data: EventData::Swap {
+            fills: vec![first_fill, second_fill],
+          },
+        }
+        .emit();
 
         Ok(())
       }
@@ -202,31 +471,153 @@ impl Processor {
         delta_b,
       } => {
         msg!("Calling AddLiquidity function");
+        Self::add_liquidity(
+          program_id, accounts, kind, delta_s, delta_a, delta_b, 0, false,
+        )
+      }
+
+      AppInstruction::AddLiquidityMinOut {
+        delta_s,
+        delta_a,
+        delta_b,
+        min_lpt,
+      } => {
+        msg!("Calling AddLiquidityMinOut function");
+        Self::add_liquidity(
+          program_id, accounts, kind, delta_s, delta_a, delta_b, min_lpt, false,
+        )
+      }
+
+      AppInstruction::AddLiquidityBalanced {
+        delta_s,
+        delta_a,
+        delta_b,
+        min_lpt,
+      } => {
+        msg!("Calling AddLiquidityBalanced function");
+        Self::add_liquidity(
+          program_id, accounts, kind, delta_s, delta_a, delta_b, min_lpt, true,
+        )
+      }
+
+      AppInstruction::RemoveLiquidity { lpt } => {
+        msg!("Calling RemoveLiquidity function");
+        Self::remove_liquidity(program_id, accounts, kind, lpt, 0, 0, 0)
+      }
+
+      AppInstruction::RemoveLiquidityMinOut {
+        lpt,
+        min_s,
+        min_a,
+        min_b,
+      } => {
+        msg!("Calling RemoveLiquidityMinOut function");
+        Self::remove_liquidity(program_id, accounts, kind, lpt, min_s, min_a, min_b)
+      }
+
+      AppInstruction::MultiRoute {
+        amount,
+        limits,
+        flags,
+      } => {
+        msg!("Calling MultiRoute function");
+        let accounts_iter = &mut accounts.iter();
+        let payer = next_account_info(accounts_iter)?;
+        let src_acc = next_account_info(accounts_iter)?;
+        let splt_program = next_account_info(accounts_iter)?;
+        let splata_program = next_account_info(accounts_iter)?;
+        let swap_program = next_account_info(accounts_iter)?;
+        let sysvar_rent_acc = next_account_info(accounts_iter)?;
+        let system_program = next_account_info(accounts_iter)?;
+        let config_acc = next_account_info(accounts_iter)?;
+
+        let config_data = Self::read_config(program_id, config_acc)?;
+        Self::is_unpaused(&config_data, Config::PAUSE_ROUTE)?;
+        Self::is_trusted(&config_data, swap_program, splt_program, splata_program)?;
+        if limits.len() > config_data.max_hops as usize {
+          return Err(AppError::TooManyHops.into());
+        }
+        let mut route = Vec::with_capacity(limits.len());
+        for _ in 0..limits.len() {
+          route.push(Hop::next(accounts_iter)?);
+        }
+        Self::read_route(src_acc, &route, swap_program)?;
+        let mut temporary = Vec::new();
+        let fills = Self::swap_along(
+          amount,
+          &limits,
+          None,
+          payer,
+          src_acc,
+          &route,
+          splt_program,
+          splata_program,
+          swap_program,
+          sysvar_rent_acc,
+          system_program,
+          &mut temporary,
+        )?;
+        // Refund the rent of the middle accounts the route created
+        Self::close_temporary(flags, &temporary, payer, splt_program)?;
+        Self::return_fills(fills[fills.len() - 1].amount_out, &fills);
+        Event {
+          kind,
+          authority: *payer.key,
+          data: EventData::Swap { fills },
+        }
+        .emit();
+
+        Ok(())
+      }
+
+      AppInstruction::SwapExactOut {
+        amount_out,
+        max_amount_in,
+      } => {
+        msg!("Calling SwapExactOut function");
         let accounts_iter = &mut accounts.iter();
         let payer = next_account_info(accounts_iter)?;
         let pool_acc = next_account_info(accounts_iter)?;
-        let lpt_acc = next_account_info(accounts_iter)?;
-        let mint_lpt_acc = next_account_info(accounts_iter)?;
-        let src_s_acc = next_account_info(accounts_iter)?;
-        let treasury_s_acc = next_account_info(accounts_iter)?;
-        let src_a_acc = next_account_info(accounts_iter)?;
-        let treasury_a_acc = next_account_info(accounts_iter)?;
-        let src_b_acc = next_account_info(accounts_iter)?;
-        let treasury_b_acc = next_account_info(accounts_iter)?;
+        let vault_acc = next_account_info(accounts_iter)?;
+        let src_acc = next_account_info(accounts_iter)?;
+        let treasury_bid_acc = next_account_info(accounts_iter)?;
+        let dst_acc = next_account_info(accounts_iter)?;
+        let mint_ask_acc = next_account_info(accounts_iter)?;
+        let treasury_ask_acc = next_account_info(accounts_iter)?;
+        let treasury_sen_acc = next_account_info(accounts_iter)?;
         let treasurer = next_account_info(accounts_iter)?;
         let splt_program = next_account_info(accounts_iter)?;
         let splata_program = next_account_info(accounts_iter)?;
         let swap_program = next_account_info(accounts_iter)?;
         let sysvar_rent_acc = next_account_info(accounts_iter)?;
         let system_program = next_account_info(accounts_iter)?;
+        let config_acc = next_account_info(accounts_iter)?;
+
+        let config_data = Self::read_config(program_id, config_acc)?;
+        Self::is_unpaused(&config_data, Config::PAUSE_SWAP)?;
+        Self::is_trusted(&config_data, swap_program, splt_program, splata_program)?;
+        // Cross-check the accounts against the pool
+        let pool_data = Self::read_pool(pool_acc, swap_program)?;
+        let bid_mint = Account::unpack(&src_acc.data.borrow())?.mint;
+        Self::is_vault(&pool_data, vault_acc)?;
+        Self::is_treasury(&pool_data, treasury_bid_acc, bid_mint)?;
+        Self::is_treasury(&pool_data, treasury_ask_acc, *mint_ask_acc.key)?;
+        Self::is_treasury(&pool_data, treasury_sen_acc, pool_data.mint_s)?;
+        Self::is_unfrozen(&[
+          src_acc,
+          treasury_bid_acc,
+          dst_acc,
+          treasury_ask_acc,
+          treasury_sen_acc,
+        ])?;
 
         // Initialize destination account just in case
-        if !Self::is_rented_and_initialized_acc(&lpt_acc)? {
+        if !Self::is_rented_and_initialized_acc(dst_acc)? {
           XSPLATA::initialize_account(
             payer,
-            lpt_acc,
+            dst_acc,
             payer,
-            mint_lpt_acc,
+            mint_ask_acc,
             system_program,
             splt_program,
             sysvar_rent_acc,
@@ -234,137 +625,2060 @@ impl Processor {
             &[],
           )?;
         }
-        // Add Liquidity
-        XSwap::add_liquidity(
-          delta_s,
-          delta_a,
-          delta_b,
+        // Estimate input amount
+        let amount_in =
+          Self::estimate_bid_amount(&pool_data, bid_mint, *mint_ask_acc.key, amount_out)?;
+        if amount_in > max_amount_in {
+          return Err(AppError::ExceededMaxAmountIn.into());
+        }
+        // Swap
+        let fill = Self::swap_with_fill(
+          amount_in,
+          amount_out,
           payer,
           pool_acc,
-          lpt_acc,
-          mint_lpt_acc,
-          src_s_acc,
-          treasury_s_acc,
-          src_a_acc,
-          treasury_a_acc,
-          src_b_acc,
-          treasury_b_acc,
+          vault_acc,
+          src_acc,
+          treasury_bid_acc,
+          dst_acc,
+          treasury_ask_acc,
+          treasury_sen_acc,
           treasurer,
           splt_program,
           swap_program,
-          &[],
         )?;
+        Self::return_fills(fill.amount_out, &[fill]);
+        Event {
+          kind,
+          authority: *payer.key,
+          data: EventData::Swap { fills: vec![fill] },
+        }
+        .emit();
 
         Ok(())
       }
 
-      AppInstruction::RemoveLiquidity { lpt } => {
-        msg!("Calling RemoveLiquidity function");
+      AppInstruction::RouteExactOut {
+        amount_out,
+        max_amount_in,
+        hops,
+        flags,
+      } => {
+        msg!("Calling RouteExactOut function");
         let accounts_iter = &mut accounts.iter();
         let payer = next_account_info(accounts_iter)?;
-        let pool_acc = next_account_info(accounts_iter)?;
-        let lpt_acc = next_account_info(accounts_iter)?;
-        let mint_lpt_acc = next_account_info(accounts_iter)?;
-        let dst_s_acc = next_account_info(accounts_iter)?;
-        let mint_s_acc = next_account_info(accounts_iter)?;
-        let treasury_s_acc = next_account_info(accounts_iter)?;
-        let dst_a_acc = next_account_info(accounts_iter)?;
-        let mint_a_acc = next_account_info(accounts_iter)?;
-        let treasury_a_acc = next_account_info(accounts_iter)?;
-        let dst_b_acc = next_account_info(accounts_iter)?;
-        let mint_b_acc = next_account_info(accounts_iter)?;
-        let treasury_b_acc = next_account_info(accounts_iter)?;
-        let treasurer = next_account_info(accounts_iter)?;
+        let src_acc = next_account_info(accounts_iter)?;
         let splt_program = next_account_info(accounts_iter)?;
         let splata_program = next_account_info(accounts_iter)?;
         let swap_program = next_account_info(accounts_iter)?;
         let sysvar_rent_acc = next_account_info(accounts_iter)?;
         let system_program = next_account_info(accounts_iter)?;
+        let config_acc = next_account_info(accounts_iter)?;
 
-        // Initialize destination account just in case
-        if !Self::is_rented_and_initialized_acc(&dst_s_acc)? {
-          XSPLATA::initialize_account(
-            payer,
-            dst_s_acc,
-            payer,
-            mint_s_acc,
-            system_program,
-            splt_program,
-            sysvar_rent_acc,
-            splata_program,
-            &[],
-          )?;
+        let config_data = Self::read_config(program_id, config_acc)?;
+        Self::is_unpaused(&config_data, Config::PAUSE_ROUTE)?;
+        Self::is_trusted(&config_data, swap_program, splt_program, splata_program)?;
+        if hops > config_data.max_hops {
+          return Err(AppError::TooManyHops.into());
         }
-        if !Self::is_rented_and_initialized_acc(&dst_a_acc)? {
-          XSPLATA::initialize_account(
-            payer,
-            dst_a_acc,
-            payer,
-            mint_a_acc,
-            system_program,
-            splt_program,
-            sysvar_rent_acc,
-            splata_program,
-            &[],
-          )?;
+        let mut route = Vec::with_capacity(hops as usize);
+        for _ in 0..hops {
+          route.push(Hop::next(accounts_iter)?);
         }
-        if !Self::is_rented_and_initialized_acc(&dst_b_acc)? {
-          XSPLATA::initialize_account(
-            payer,
-            dst_b_acc,
-            payer,
-            mint_b_acc,
-            system_program,
-            splt_program,
-            sysvar_rent_acc,
-            splata_program,
-            &[],
-          )?;
+        let pools = Self::read_route(src_acc, &route, swap_program)?;
+        // Estimate hop amounts backwards from the expected output
+        let src_mint = Account::unpack(&src_acc.data.borrow())?.mint;
+        let mut amounts = vec![0; route.len() + 1];
+        amounts[route.len()] = amount_out;
+        for (i, hop) in route.iter().enumerate().rev() {
+          let pool_data = &pools[i];
+          let bid_mint = match i {
+            0 => src_mint,
+            _ => *route[i - 1].mint_ask_acc.key,
+          };
+          amounts[i] =
+            Self::estimate_bid_amount(pool_data, bid_mint, *hop.mint_ask_acc.key, amounts[i + 1])?;
         }
-        // Remove Liquidity
-        XSwap::remove_liquidity(
-          lpt,
+        if amounts[0] > max_amount_in {
+          return Err(AppError::ExceededMaxAmountIn.into());
+        }
+
+        let mut temporary = Vec::new();
+        let fills = Self::swap_along(
+          amounts[0],
+          &amounts[1..],
+          Some(&amounts[..route.len()]),
           payer,
-          pool_acc,
-          lpt_acc,
-          mint_lpt_acc,
-          dst_s_acc,
-          treasury_s_acc,
-          dst_a_acc,
-          treasury_a_acc,
-          dst_b_acc,
-          treasury_b_acc,
-          treasurer,
+          src_acc,
+          &route,
           splt_program,
+          splata_program,
           swap_program,
-          &[],
+          sysvar_rent_acc,
+          system_program,
+          &mut temporary,
         )?;
+        // Refund the rent of the middle accounts the route created
+        Self::close_temporary(flags, &temporary, payer, splt_program)?;
+        Self::return_fills(fills[fills.len() - 1].amount_out, &fills);
+        Event {
+          kind,
+          authority: *payer.key,
+          data: EventData::Swap { fills },
+        }
+        .emit();
 
         Ok(())
       }
-    }
-  }
 
-  pub fn is_rented_and_initialized_acc(acc: &AccountInfo) -> Result<bool, ProgramError> {
-    let is_initialized: bool;
-    if (&acc.data.borrow()).len() == 0 {
-      is_initialized = false
-    } else {
-      let acc_data = Account::unpack_unchecked(&acc.data.borrow())?;
-      is_initialized = acc_data.is_initialized();
-    }
-    Ok(is_initialized)
-  }
+      AppInstruction::RouteMinOut {
+        amount,
+        min_amount_out,
+        hops,
+        flags,
+      } => {
+        msg!("Calling RouteMinOut function");
+        let accounts_iter = &mut accounts.iter();
+        let payer = next_account_info(accounts_iter)?;
+        let src_acc = next_account_info(accounts_iter)?;
+        let splt_program = next_account_info(accounts_iter)?;
+        let splata_program = next_account_info(accounts_iter)?;
+        let swap_program = next_account_info(accounts_iter)?;
+        let sysvar_rent_acc = next_account_info(accounts_iter)?;
+        let system_program = next_account_info(accounts_iter)?;
+        let config_acc = next_account_info(accounts_iter)?;
 
-  pub fn parse_reserve(pool_data: &Pool, mint: Pubkey) -> Option<u64> {
-    if pool_data.mint_a == mint {
-      return Some(pool_data.reserve_a);
-    } else if pool_data.mint_b == mint {
-      return Some(pool_data.reserve_b);
-    } else if pool_data.mint_s == mint {
-      return Some(pool_data.reserve_s);
-    } else {
-      return None;
-    }
+        let config_data = Self::read_config(program_id, config_acc)?;
+        Self::is_unpaused(&config_data, Config::PAUSE_ROUTE)?;
+        Self::is_trusted(&config_data, swap_program, splt_program, splata_program)?;
+        if hops > config_data.max_hops {
+          return Err(AppError::TooManyHops.into());
+        }
+        let mut route = Vec::with_capacity(hops as usize);
+        for _ in 0..hops {
+          route.push(Hop::next(accounts_iter)?);
+        }
+        Self::read_route(src_acc, &route, swap_program)?;
+        let dst_acc = route[route.len() - 1].dst_acc;
+        // The destination may not exist yet
+        let mut prev_amount_out = 0;
+        if Self::is_rented_and_initialized_acc(dst_acc)? {
+          prev_amount_out = Self::read_amount(dst_acc)?;
+        }
+        // Only the final output is guarded
+        let mut temporary = Vec::new();
+        let fills = Self::swap_along(
+          amount,
+          &vec![0; route.len()],
+          None,
+          payer,
+          src_acc,
+          &route,
+          splt_program,
+          splata_program,
+          swap_program,
+          sysvar_rent_acc,
+          system_program,
+          &mut temporary,
+        )?;
+        let amount_out = Self::read_amount(dst_acc)?
+          .checked_sub(prev_amount_out)
+          .ok_or(AppError::Overflow)?;
+        if amount_out < min_amount_out {
+          return Err(AppError::InsufficientAmountOut.into());
+        }
+        // Refund the rent of the middle accounts the route created
+        Self::close_temporary(flags, &temporary, payer, splt_program)?;
+        Self::return_fills(amount_out, &fills);
+        Event {
+          kind,
+          authority: *payer.key,
+          data: EventData::Swap { fills },
+        }
+        .emit();
+
+        Ok(())
+      }
+
+      AppInstruction::BridgeRoute {
+        amount,
+        first_limit,
+        bridge_limit,
+        second_limit,
+        flags,
+      } => {
+        msg!("Calling BridgeRoute function");
+        let accounts_iter = &mut accounts.iter();
+        let payer = next_account_info(accounts_iter)?;
+        let src_acc = next_account_info(accounts_iter)?;
+        let splt_program = next_account_info(accounts_iter)?;
+        let splata_program = next_account_info(accounts_iter)?;
+        let swap_program = next_account_info(accounts_iter)?;
+        let sysvar_rent_acc = next_account_info(accounts_iter)?;
+        let system_program = next_account_info(accounts_iter)?;
+        let config_acc = next_account_info(accounts_iter)?;
+        let first_hop = Hop::next(accounts_iter)?;
+        let bridge_hop = Hop::next(accounts_iter)?;
+        let second_hop = Hop::next(accounts_iter)?;
+
+        let config_data = Self::read_config(program_id, config_acc)?;
+        Self::is_unpaused(&config_data, Config::PAUSE_ROUTE)?;
+        Self::is_trusted(&config_data, swap_program, splt_program, splata_program)?;
+        let route = [first_hop, bridge_hop, second_hop];
+        if route.len() > config_data.max_hops as usize {
+          return Err(AppError::TooManyHops.into());
+        }
+        let pools = Self::read_route(src_acc, &route, swap_program)?;
+        let (first_pool_data, bridge_pool_data, second_pool_data) = (pools[0], pools[1], pools[2]);
+        // The route must go A -> S1 -> S2 -> B through a pool holding both primary mints
+        if *route[0].mint_ask_acc.key != first_pool_data.mint_s
+          || *route[1].mint_ask_acc.key != second_pool_data.mint_s
+          || Self::parse_reserve(&bridge_pool_data, first_pool_data.mint_s).is_none()
+          || Self::parse_reserve(&bridge_pool_data, second_pool_data.mint_s).is_none()
+        {
+          return Err(AppError::UnmatchedPrimaryMints.into());
+        }
+        let mut temporary = Vec::new();
+        let fills = Self::swap_along(
+          amount,
+          &[first_limit, bridge_limit, second_limit],
+          None,
+          payer,
+          src_acc,
+          &route,
+          splt_program,
+          splata_program,
+          swap_program,
+          sysvar_rent_acc,
+          system_program,
+          &mut temporary,
+        )?;
+        // Refund the rent of the middle accounts the route created
+        Self::close_temporary(flags, &temporary, payer, splt_program)?;
+        Self::return_fills(fills[fills.len() - 1].amount_out, &fills);
+        Event {
+          kind,
+          authority: *payer.key,
+          data: EventData::Swap { fills },
+        }
+        .emit();
+
+        Ok(())
+      }
+
+      AppInstruction::SplitRoute {
+        min_amount_out,
+        paths,
+        flags,
+      } => {
+        msg!("Calling SplitRoute function");
+        let accounts_iter = &mut accounts.iter();
+        let payer = next_account_info(accounts_iter)?;
+        let src_acc = next_account_info(accounts_iter)?;
+        let dst_acc = next_account_info(accounts_iter)?;
+        let splt_program = next_account_info(accounts_iter)?;
+        let splata_program = next_account_info(accounts_iter)?;
+        let swap_program = next_account_info(accounts_iter)?;
+        let sysvar_rent_acc = next_account_info(accounts_iter)?;
+        let system_program = next_account_info(accounts_iter)?;
+        let config_acc = next_account_info(accounts_iter)?;
+
+        let config_data = Self::read_config(program_id, config_acc)?;
+        Self::is_unpaused(&config_data, Config::PAUSE_ROUTE)?;
+        Self::is_trusted(&config_data, swap_program, splt_program, splata_program)?;
+        if paths.iter().any(|&(_, hops)| hops > config_data.max_hops) {
+          return Err(AppError::TooManyHops.into());
+        }
+        // The destination may not exist yet
+        let mut prev_amount_out = 0;
+        if Self::is_rented_and_initialized_acc(dst_acc)? {
+          prev_amount_out = Self::read_amount(dst_acc)?;
+        }
+        let mut routes = Vec::with_capacity(paths.len());
+        for (amount, hops) in paths {
+          let mut route = Vec::with_capacity(hops as usize);
+          for _ in 0..hops {
+            route.push(Hop::next(accounts_iter)?);
+          }
+          // Every path must end in the shared destination
+          if route[route.len() - 1].dst_acc.key != dst_acc.key {
+            return Err(AppError::UnmatchedDestination.into());
+          }
+          Self::read_route(src_acc, &route, swap_program)?;
+          routes.push((amount, route));
+        }
+        let mut fills = Vec::new();
+        let mut temporary = Vec::new();
+        for (amount, route) in routes {
+          fills.extend(Self::swap_along(
+            amount,
+            &vec![0; route.len()],
+            None,
+            payer,
+            src_acc,
+            &route,
+            splt_program,
+            splata_program,
+            swap_program,
+            sysvar_rent_acc,
+            system_program,
+            &mut temporary,
+          )?);
+        }
+        let amount_out = Self::read_amount(dst_acc)?
+          .checked_sub(prev_amount_out)
+          .ok_or(AppError::Overflow)?;
+        if amount_out < min_amount_out {
+          return Err(AppError::InsufficientAmountOut.into());
+        }
+        // Refund the rent of the middle accounts the route created
+        Self::close_temporary(flags, &temporary, payer, splt_program)?;
+        Self::return_fills(amount_out, &fills);
+        Event {
+          kind,
+          authority: *payer.key,
+          data: EventData::Swap { fills },
+        }
+        .emit();
+
+        Ok(())
+      }
+
+      AppInstruction::SmartSwap {
+        amount,
+        min_amount_out,
+        flags,
+      } => {
+        msg!("Calling SmartSwap function");
+        let accounts_iter = &mut accounts.iter();
+        let payer = next_account_info(accounts_iter)?;
+        let src_acc = next_account_info(accounts_iter)?;
+        let dst_acc = next_account_info(accounts_iter)?;
+        let mint_ask_acc = next_account_info(accounts_iter)?;
+        let sen_acc = next_account_info(accounts_iter)?;
+        let mint_sen_acc = next_account_info(accounts_iter)?;
+        let splt_program = next_account_info(accounts_iter)?;
+        let splata_program = next_account_info(accounts_iter)?;
+        let swap_program = next_account_info(accounts_iter)?;
+        let sysvar_rent_acc = next_account_info(accounts_iter)?;
+        let system_program = next_account_info(accounts_iter)?;
+        let config_acc = next_account_info(accounts_iter)?;
+
+        let config_data = Self::read_config(program_id, config_acc)?;
+        Self::is_unpaused(&config_data, Config::PAUSE_SWAP)?;
+        Self::is_trusted(&config_data, swap_program, splt_program, splata_program)?;
+        // The remaining accounts are candidate pools in groups of 6
+        let mut candidates = Vec::new();
+        while !accounts_iter.as_slice().is_empty() {
+          candidates.push(Candidate::next(accounts_iter, swap_program)?);
+        }
+        Self::is_unfrozen(&[src_acc, sen_acc, dst_acc])?;
+
+        // Evaluate the direct paths and the two-hop paths via the primary mint
+        let bid_mint = Account::unpack(&src_acc.data.borrow())?.mint;
+        let ask_mint = *mint_ask_acc.key;
+        let sen_mint = *mint_sen_acc.key;
+        let mut best_amount_out = 0;
+        let mut best = None;
+        for (i, first) in candidates.iter().enumerate() {
+          if first.is_frozen {
+            continue;
+          }
+          if let Some(amount_out) = first.quote(bid_mint, ask_mint, amount) {
+            if amount_out > best_amount_out {
+              best_amount_out = amount_out;
+              best = Some((i, None));
+            }
+          }
+          if first.pool_data.mint_s != sen_mint {
+            continue;
+          }
+          let middle_amount = match first.quote(bid_mint, sen_mint, amount) {
+            Some(middle_amount) => middle_amount,
+            None => continue,
+          };
+          for (j, second) in candidates.iter().enumerate() {
+            if i == j || second.is_frozen || second.pool_data.mint_s != sen_mint {
+              continue;
+            }
+            if let Some(amount_out) = second.quote(sen_mint, ask_mint, middle_amount) {
+              if amount_out > best_amount_out {
+                best_amount_out = amount_out;
+                best = Some((i, Some(j)));
+              }
+            }
+          }
+        }
+        let (first, second) = best.ok_or(AppError::NoAvailableRoute)?;
+
+        // Initialize destination account just in case
+        if !Self::is_rented_and_initialized_acc(dst_acc)? {
+          XSPLATA::initialize_account(
+            payer,
+            dst_acc,
+            payer,
+            mint_ask_acc,
+            system_program,
+            splt_program,
+            sysvar_rent_acc,
+            splata_program,
+            &[],
+          )?;
+        }
+        let prev_amount_out = Self::read_amount(dst_acc)?;
+        let mut temporary = Vec::new();
+        let fills = match second {
+          None => {
+            let fill = candidates[first].swap(
+              amount,
+              0,
+              payer,
+              src_acc,
+              dst_acc,
+              bid_mint,
+              ask_mint,
+              splt_program,
+              swap_program,
+            )?;
+            vec![fill]
+          }
+          Some(second) => {
+            // Initialize middle account just in case
+            if !Self::is_rented_and_initialized_acc(sen_acc)? {
+              XSPLATA::initialize_account(
+                payer,
+                sen_acc,
+                payer,
+                mint_sen_acc,
+                system_program,
+                splt_program,
+                sysvar_rent_acc,
+                splata_program,
+                &[],
+              )?;
+              temporary.push(sen_acc);
+            }
+            let first_fill = candidates[first].swap(
+              amount,
+              0,
+              payer,
+              src_acc,
+              sen_acc,
+              bid_mint,
+              sen_mint,
+              splt_program,
+              swap_program,
+            )?;
+            let second_fill = candidates[second].swap(
+              first_fill.amount_out,
+              0,
+              payer,
+              sen_acc,
+              dst_acc,
+              sen_mint,
+              ask_mint,
+              splt_program,
+              swap_program,
+            )?;
+            vec![first_fill, second_fill]
+          }
+        };
+        let amount_out = Self::read_amount(dst_acc)?
+          .checked_sub(prev_amount_out)
+          .ok_or(AppError::Overflow)?;
+        if amount_out < min_amount_out {
+          return Err(AppError::InsufficientAmountOut.into());
+        }
+        // Refund the rent of the middle account if the swap created it
+        Self::close_temporary(flags, &temporary, payer, splt_program)?;
+        Self::return_fills(amount_out, &fills);
+        Event {
+          kind,
+          authority: *payer.key,
+          data: EventData::Swap { fills },
+        }
+        .emit();
+
+        Ok(())
+      }
+
+      AppInstruction::Quote { amount, hops } => {
+        msg!("Calling Quote function");
+        let accounts_iter = &mut accounts.iter();
+        let mint_bid_acc = next_account_info(accounts_iter)?;
+        let swap_program = next_account_info(accounts_iter)?;
+        let config_acc = next_account_info(accounts_iter)?;
+
+        // Callers rely on the quote, so only read pools of the trusted swap program
+        let config_data = Self::read_config(program_id, config_acc)?;
+        if config_data.swap_program != *swap_program.key {
+          return Err(AppError::IncorrectProgramId.into());
+        }
+        // Return data: the final amount, then ask amount, fee, earning
+        // and price impact of every hop
+        let mut data = Vec::with_capacity(8 + 32 * hops as usize);
+        data.extend_from_slice(&[0; 8]);
+        let mut bid_mint = *mint_bid_acc.key;
+        let mut bid_amount = amount;
+        for hop in 0..hops {
+          let pool_acc = next_account_info(accounts_iter)?;
+          let mint_ask_acc = next_account_info(accounts_iter)?;
+          let pool_data = Self::read_pool(pool_acc, swap_program)?;
+          let (ask_amount, fee, earning, price_impact) =
+            Self::quote(&pool_data, bid_mint, *mint_ask_acc.key, bid_amount)?;
+          msg!(
+            "Quote: hop {} amount_in {} amount_out {} fee {} earning {} price_impact {}",
+            hop,
+            bid_amount,
+            ask_amount,
+            fee,
+            earning,
+            price_impact
+          );
+          data.extend_from_slice(&ask_amount.to_le_bytes());
+          data.extend_from_slice(&fee.to_le_bytes());
+          data.extend_from_slice(&earning.to_le_bytes());
+          data.extend_from_slice(&price_impact.to_le_bytes());
+          bid_mint = *mint_ask_acc.key;
+          bid_amount = ask_amount;
+        }
+        data[..8].copy_from_slice(&bid_amount.to_le_bytes());
+        set_return_data(&data);
+
+        Ok(())
+      }
+
+      AppInstruction::InitializeConfig { max_hops } => {
+        msg!("Calling InitializeConfig function");
+        let accounts_iter = &mut accounts.iter();
+        let payer = next_account_info(accounts_iter)?;
+        let admin = next_account_info(accounts_iter)?;
+        let config_acc = next_account_info(accounts_iter)?;
+        let system_program = next_account_info(accounts_iter)?;
+        let sysvar_rent_acc = next_account_info(accounts_iter)?;
+        let swap_program = next_account_info(accounts_iter)?;
+        let splt_program = next_account_info(accounts_iter)?;
+        let splata_program = next_account_info(accounts_iter)?;
+        let program_data_acc = next_account_info(accounts_iter)?;
+
+        Self::is_upgrade_authority(program_id, program_data_acc, payer)?;
+        let (config_key, bump) = Config::address(program_id);
+        if config_key != *config_acc.key {
+          return Err(AppError::InvalidConfig.into());
+        }
+        if config_acc.data_len() > 0 {
+          return Err(AppError::ConstructorOnce.into());
+        }
+        // Create the config account at the program address, the address may
+        // already hold lamports so fund the shortfall then allocate and assign
+        let rent = Rent::from_account_info(sysvar_rent_acc)?;
+        let lamports = rent
+          .minimum_balance(Config::LEN)
+          .saturating_sub(config_acc.lamports());
+        if lamports > 0 {
+          invoke_signed(
+            &system_instruction::transfer(payer.key, config_acc.key, lamports),
+            &[payer.clone(), config_acc.clone(), system_program.clone()],
+            &[],
+          )?;
+        }
+        let seed: &[&[&[u8]]] = &[&[Config::SEED, &[bump]]];
+        invoke_signed(
+          &system_instruction::allocate(config_acc.key, Config::LEN as u64),
+          &[config_acc.clone(), system_program.clone()],
+          seed,
+        )?;
+        invoke_signed(
+          &system_instruction::assign(config_acc.key, program_id),
+          &[config_acc.clone(), system_program.clone()],
+          seed,
+        )?;
+        let config_data = Config {
+          version: Config::VERSION,
+          is_initialized: true,
+          admin: *admin.key,
+          max_hops,
+          paused: 0,
+          swap_program: *swap_program.key,
+          splt_program: *splt_program.key,
+          splata_program: *splata_program.key,
+        };
+        Config::pack(config_data, &mut config_acc.data.borrow_mut())?;
+        Event {
+          kind,
+          authority: *admin.key,
+          data: EventData::Admin,
+        }
+        .emit();
+
+        Ok(())
+      }
+
+      AppInstruction::UpdateConfig { max_hops } => {
+        msg!("Calling UpdateConfig function");
+        let accounts_iter = &mut accounts.iter();
+        let admin = next_account_info(accounts_iter)?;
+        let config_acc = next_account_info(accounts_iter)?;
+
+        let mut config_data = Self::read_config(program_id, config_acc)?;
+        Self::is_admin(&config_data, admin)?;
+        config_data.max_hops = max_hops;
+        Config::pack(config_data, &mut config_acc.data.borrow_mut())?;
+        Event {
+          kind,
+          authority: *admin.key,
+          data: EventData::Admin,
+        }
+        .emit();
+
+        Ok(())
+      }
+
+      AppInstruction::SetPaused { paused } => {
+        msg!("Calling SetPaused function");
+        let accounts_iter = &mut accounts.iter();
+        let admin = next_account_info(accounts_iter)?;
+        let config_acc = next_account_info(accounts_iter)?;
+
+        let mut config_data = Self::read_config(program_id, config_acc)?;
+        Self::is_admin(&config_data, admin)?;
+        config_data.paused = paused;
+        Config::pack(config_data, &mut config_acc.data.borrow_mut())?;
+        Event {
+          kind,
+          authority: *admin.key,
+          data: EventData::Admin,
+        }
+        .emit();
+
+        Ok(())
+      }
+
+      AppInstruction::UpdatePrograms => {
+        msg!("Calling UpdatePrograms function");
+        let accounts_iter = &mut accounts.iter();
+        let admin = next_account_info(accounts_iter)?;
+        let config_acc = next_account_info(accounts_iter)?;
+        let swap_program = next_account_info(accounts_iter)?;
+        let splt_program = next_account_info(accounts_iter)?;
+        let splata_program = next_account_info(accounts_iter)?;
+
+        let mut config_data = Self::read_config(program_id, config_acc)?;
+        Self::is_admin(&config_data, admin)?;
+        config_data.swap_program = *swap_program.key;
+        config_data.splt_program = *splt_program.key;
+        config_data.splata_program = *splata_program.key;
+        Config::pack(config_data, &mut config_acc.data.borrow_mut())?;
+        Event {
+          kind,
+          authority: *admin.key,
+          data: EventData::Admin,
+        }
+        .emit();
+
+        Ok(())
+      }
+
+      AppInstruction::TransferAdmin => {
+        msg!("Calling TransferAdmin function");
+        let accounts_iter = &mut accounts.iter();
+        let admin = next_account_info(accounts_iter)?;
+        let config_acc = next_account_info(accounts_iter)?;
+        let new_admin = next_account_info(accounts_iter)?;
+
+        let mut config_data = Self::read_config(program_id, config_acc)?;
+        Self::is_admin(&config_data, admin)?;
+        config_data.admin = *new_admin.key;
+        Config::pack(config_data, &mut config_acc.data.borrow_mut())?;
+        Event {
+          kind,
+          authority: *admin.key,
+          data: EventData::Admin,
+        }
+        .emit();
+
+        Ok(())
+      }
+
+      AppInstruction::ZapIn { amount, min_lpt } => {
+        msg!("Calling ZapIn function");
+        let accounts_iter = &mut accounts.iter();
+        let payer = next_account_info(accounts_iter)?;
+        let pool_acc = next_account_info(accounts_iter)?;
+        let vault_acc = next_account_info(accounts_iter)?;
+        let src_acc = next_account_info(accounts_iter)?;
+        let lpt_acc = next_account_info(accounts_iter)?;
+        let mint_lpt_acc = next_account_info(accounts_iter)?;
+        let src_s_acc = next_account_info(accounts_iter)?;
+        let mint_s_acc = next_account_info(accounts_iter)?;
+        let treasury_s_acc = next_account_info(accounts_iter)?;
+        let src_a_acc = next_account_info(accounts_iter)?;
+        let mint_a_acc = next_account_info(accounts_iter)?;
+        let treasury_a_acc = next_account_info(accounts_iter)?;
+        let src_b_acc = next_account_info(accounts_iter)?;
+        let mint_b_acc = next_account_info(accounts_iter)?;
+        let treasury_b_acc = next_account_info(accounts_iter)?;
+        let treasurer = next_account_info(accounts_iter)?;
+        let splt_program = next_account_info(accounts_iter)?;
+        let splata_program = next_account_info(accounts_iter)?;
+        let swap_program = next_account_info(accounts_iter)?;
+        let sysvar_rent_acc = next_account_info(accounts_iter)?;
+        let system_program = next_account_info(accounts_iter)?;
+        let config_acc = next_account_info(accounts_iter)?;
+
+        let config_data = Self::read_config(program_id, config_acc)?;
+        Self::is_unpaused(&config_data, Config::PAUSE_ADD_LIQUIDITY)?;
+        Self::is_trusted(&config_data, swap_program, splt_program, splata_program)?;
+        // Cross-check the accounts against the pool
+        let pool_data = Self::read_pool(pool_acc, swap_program)?;
+        Self::is_vault(&pool_data, vault_acc)?;
+        Self::is_mint_lpt(&pool_data, mint_lpt_acc)?;
+        if pool_data.mint_s != *mint_s_acc.key
+          || pool_data.mint_a != *mint_a_acc.key
+          || pool_data.mint_b != *mint_b_acc.key
+        {
+          return Err(AppError::UnmatchedMint.into());
+        }
+        Self::is_treasury(&pool_data, treasury_s_acc, pool_data.mint_s)?;
+        Self::is_treasury(&pool_data, treasury_a_acc, pool_data.mint_a)?;
+        Self::is_treasury(&pool_data, treasury_b_acc, pool_data.mint_b)?;
+        Self::is_unfrozen(&[
+          src_acc,
+          lpt_acc,
+          src_s_acc,
+          treasury_s_acc,
+          src_a_acc,
+          treasury_a_acc,
+          src_b_acc,
+          treasury_b_acc,
+        ])?;
+
+        // The source stands for its own reserve, the two others receive the swaps
+        let sides = [
+          (src_s_acc, mint_s_acc, treasury_s_acc, pool_data.reserve_s),
+          (src_a_acc, mint_a_acc, treasury_a_acc, pool_data.reserve_a),
+          (src_b_acc, mint_b_acc, treasury_b_acc, pool_data.reserve_b),
+        ];
+        let bid_mint = Account::unpack(&src_acc.data.borrow())?.mint;
+        let bid = sides
+          .iter()
+          .position(|(_, mint_acc, _, _)| *mint_acc.key == bid_mint)
+          .ok_or(AppError::UnmatchedMint)?;
+        if sides[bid].0.key != src_acc.key {
+          return Err(AppError::UnmatchedSource.into());
+        }
+        let (first, second) = ((bid + 1) % 3, (bid + 2) % 3);
+        // Swaps touching the primary mint are exempted from earning
+        let (first_amount, second_amount) = Oracle::zap_in(
+          amount,
+          sides[bid].3,
+          sides[first].3,
+          sides[second].3,
+          bid == 0 || first == 0,
+          bid == 0 || second == 0,
+        )
+        .ok_or(AppError::Overflow)?;
+
+        // Initialize destination accounts just in case
+        if !Self::is_rented_and_initialized_acc(lpt_acc)? {
+          XSPLATA::initialize_account(
+            payer,
+            lpt_acc,
+            payer,
+            mint_lpt_acc,
+            system_program,
+            splt_program,
+            sysvar_rent_acc,
+            splata_program,
+            &[],
+          )?;
+        }
+        for &side in [first, second].iter() {
+          if !Self::is_rented_and_initialized_acc(sides[side].0)? {
+            XSPLATA::initialize_account(
+              payer,
+              sides[side].0,
+              payer,
+              sides[side].1,
+              system_program,
+              splt_program,
+              sysvar_rent_acc,
+              splata_program,
+              &[],
+            )?;
+          }
+        }
+        // Swap into the two other reserves
+        let mut fills = Vec::with_capacity(2);
+        for &(side, swap_amount) in [(first, first_amount), (second, second_amount)].iter() {
+          fills.push(Self::swap_with_fill(
+            swap_amount,
+            0,
+            payer,
+            pool_acc,
+            vault_acc,
+            src_acc,
+            sides[bid].2,
+            sides[side].0,
+            sides[side].2,
+            treasury_s_acc,
+            treasurer,
+            splt_program,
+            swap_program,
+          )?);
+        }
+        let mut deltas = [0; 3];
+        deltas[bid] = amount
+          .checked_sub(first_amount)
+          .and_then(|left_amount| left_amount.checked_sub(second_amount))
+          .ok_or(AppError::Overflow)?;
+        deltas[first] = fills[0].amount_out;
+        deltas[second] = fills[1].amount_out;
+        let prev_lpt = Self::read_amount(lpt_acc)?;
+        // Add Liquidity
+        XSwap::add_liquidity(
+          deltas[0],
+          deltas[1],
+          deltas[2],
+          payer,
+          pool_acc,
+          lpt_acc,
+          mint_lpt_acc,
+          src_s_acc,
+          treasury_s_acc,
+          src_a_acc,
+          treasury_a_acc,
+          src_b_acc,
+          treasury_b_acc,
+          treasurer,
+          splt_program,
+          swap_program,
+          &[],
+        )?;
+        let lpt = Self::read_amount(lpt_acc)?
+          .checked_sub(prev_lpt)
+          .ok_or(AppError::Overflow)?;
+        if lpt < min_lpt {
+          return Err(AppError::InsufficientAmountOut.into());
+        }
+        // Return data: the minted lpt
+        set_return_data(&lpt.to_le_bytes());
+        Event {
+          kind,
+          authority: *payer.key,
+          data: EventData::Swap { fills },
+        }
+        .emit();
+        Event {
+          kind,
+          authority: *payer.key,
+          data: EventData::Liquidity {
+            pool: *pool_acc.key,
+            lpt,
+            amount_s: deltas[0],
+            amount_a: deltas[1],
+            amount_b: deltas[2],
+          },
+        }
+        .emit();
+
+        Ok(())
+      }
+
+      AppInstruction::ZapOut {
+        lpt,
+        min_amount_out,
+      } => {
+        msg!("Calling ZapOut function");
+        let accounts_iter = &mut accounts.iter();
+        let payer = next_account_info(accounts_iter)?;
+        let pool_acc = next_account_info(accounts_iter)?;
+        let vault_acc = next_account_info(accounts_iter)?;
+        let lpt_acc = next_account_info(accounts_iter)?;
+        let mint_lpt_acc = next_account_info(accounts_iter)?;
+        let mint_ask_acc = next_account_info(accounts_iter)?;
+        let dst_s_acc = next_account_info(accounts_iter)?;
+        let mint_s_acc = next_account_info(accounts_iter)?;
+        let treasury_s_acc = next_account_info(accounts_iter)?;
+        let dst_a_acc = next_account_info(accounts_iter)?;
+        let mint_a_acc = next_account_info(accounts_iter)?;
+        let treasury_a_acc = next_account_info(accounts_iter)?;
+        let dst_b_acc = next_account_info(accounts_iter)?;
+        let mint_b_acc = next_account_info(accounts_iter)?;
+        let treasury_b_acc = next_account_info(accounts_iter)?;
+        let treasurer = next_account_info(accounts_iter)?;
+        let splt_program = next_account_info(accounts_iter)?;
+        let splata_program = next_account_info(accounts_iter)?;
+        let swap_program = next_account_info(accounts_iter)?;
+        let sysvar_rent_acc = next_account_info(accounts_iter)?;
+        let system_program = next_account_info(accounts_iter)?;
+        let config_acc = next_account_info(accounts_iter)?;
+
+        let config_data = Self::read_config(program_id, config_acc)?;
+        Self::is_unpaused(&config_data, Config::PAUSE_REMOVE_LIQUIDITY)?;
+        Self::is_trusted(&config_data, swap_program, splt_program, splata_program)?;
+        // Cross-check the accounts against the pool
+        let pool_data = Self::read_pool(pool_acc, swap_program)?;
+        Self::is_vault(&pool_data, vault_acc)?;
+        Self::is_mint_lpt(&pool_data, mint_lpt_acc)?;
+        if pool_data.mint_s != *mint_s_acc.key
+          || pool_data.mint_a != *mint_a_acc.key
+          || pool_data.mint_b != *mint_b_acc.key
+        {
+          return Err(AppError::UnmatchedMint.into());
+        }
+        Self::is_treasury(&pool_data, treasury_s_acc, pool_data.mint_s)?;
+        Self::is_treasury(&pool_data, treasury_a_acc, pool_data.mint_a)?;
+        Self::is_treasury(&pool_data, treasury_b_acc, pool_data.mint_b)?;
+        Self::is_unfrozen(&[
+          lpt_acc,
+          dst_s_acc,
+          treasury_s_acc,
+          dst_a_acc,
+          treasury_a_acc,
+          dst_b_acc,
+          treasury_b_acc,
+        ])?;
+
+        // The chosen reserve collects the two others
+        let sides = [
+          (dst_s_acc, mint_s_acc, treasury_s_acc),
+          (dst_a_acc, mint_a_acc, treasury_a_acc),
+          (dst_b_acc, mint_b_acc, treasury_b_acc),
+        ];
+        let ask = sides
+          .iter()
+          .position(|(_, mint_acc, _)| mint_acc.key == mint_ask_acc.key)
+          .ok_or(AppError::UnmatchedMint)?;
+        let dst_acc = sides[ask].0;
+
+        // Initialize destination accounts just in case
+        for &(acc, mint_acc, _) in sides.iter() {
+          if !Self::is_rented_and_initialized_acc(acc)? {
+            XSPLATA::initialize_account(
+              payer,
+              acc,
+              payer,
+              mint_acc,
+              system_program,
+              splt_program,
+              sysvar_rent_acc,
+              splata_program,
+              &[],
+            )?;
+          }
+        }
+        let prev_lpt = Self::read_amount(lpt_acc)?;
+        let mut prev_amounts = [0; 3];
+        for (prev_amount, &(acc, _, _)) in prev_amounts.iter_mut().zip(sides.iter()) {
+          *prev_amount = Self::read_amount(acc)?;
+        }
+        // Remove Liquidity
+        XSwap::remove_liquidity(
+          lpt,
+          payer,
+          pool_acc,
+          lpt_acc,
+          mint_lpt_acc,
+          dst_s_acc,
+          treasury_s_acc,
+          dst_a_acc,
+          treasury_a_acc,
+          dst_b_acc,
+          treasury_b_acc,
+          treasurer,
+          splt_program,
+          swap_program,
+          &[],
+        )?;
+        let mut deltas = [0; 3];
+        for (i, &(acc, _, _)) in sides.iter().enumerate() {
+          deltas[i] = Self::read_amount(acc)?
+            .checked_sub(prev_amounts[i])
+            .ok_or(AppError::Overflow)?;
+        }
+        // Swap the two other legs into the chosen reserve
+        let mut fills = Vec::with_capacity(2);
+        for &side in [(ask + 1) % 3, (ask + 2) % 3].iter() {
+          if deltas[side] == 0 {
+            continue;
+          }
+          fills.push(Self::swap_with_fill(
+            deltas[side],
+            0,
+            payer,
+            pool_acc,
+            vault_acc,
+            sides[side].0,
+            sides[side].2,
+            dst_acc,
+            sides[ask].2,
+            treasury_s_acc,
+            treasurer,
+            splt_program,
+            swap_program,
+          )?);
+        }
+        let amount_out = Self::read_amount(dst_acc)?
+          .checked_sub(prev_amounts[ask])
+          .ok_or(AppError::Overflow)?;
+        if amount_out < min_amount_out {
+          return Err(AppError::InsufficientAmountOut.into());
+        }
+        Self::return_fills(amount_out, &fills);
+        Event {
+          kind,
+          authority: *payer.key,
+          data: EventData::Liquidity {
+            pool: *pool_acc.key,
+            lpt: prev_lpt
+              .checked_sub(Self::read_amount(lpt_acc)?)
+              .ok_or(AppError::Overflow)?,
+            amount_s: deltas[0],
+            amount_a: deltas[1],
+            amount_b: deltas[2],
+          },
+        }
+        .emit();
+        Event {
+          kind,
+          authority: *payer.key,
+          data: EventData::Swap { fills },
+        }
+        .emit();
+
+        Ok(())
+      }
+
+      AppInstruction::MigrateLiquidity { lpt, min_lpt } => {
+        msg!("Calling MigrateLiquidity function");
+        let accounts_iter = &mut accounts.iter();
+        let payer = next_account_info(accounts_iter)?;
+        let old_pool_acc = next_account_info(accounts_iter)?;
+        let old_vault_acc = next_account_info(accounts_iter)?;
+        let old_lpt_acc = next_account_info(accounts_iter)?;
+        let old_mint_lpt_acc = next_account_info(accounts_iter)?;
+        let old_treasurer = next_account_info(accounts_iter)?;
+        let old_s_acc = next_account_info(accounts_iter)?;
+        let old_mint_s_acc = next_account_info(accounts_iter)?;
+        let old_treasury_s_acc = next_account_info(accounts_iter)?;
+        let old_a_acc = next_account_info(accounts_iter)?;
+        let old_mint_a_acc = next_account_info(accounts_iter)?;
+        let old_treasury_a_acc = next_account_info(accounts_iter)?;
+        let old_b_acc = next_account_info(accounts_iter)?;
+        let old_mint_b_acc = next_account_info(accounts_iter)?;
+        let old_treasury_b_acc = next_account_info(accounts_iter)?;
+        let new_pool_acc = next_account_info(accounts_iter)?;
+        let new_lpt_acc = next_account_info(accounts_iter)?;
+        let new_mint_lpt_acc = next_account_info(accounts_iter)?;
+        let new_treasurer = next_account_info(accounts_iter)?;
+        let new_s_acc = next_account_info(accounts_iter)?;
+        let new_mint_s_acc = next_account_info(accounts_iter)?;
+        let new_treasury_s_acc = next_account_info(accounts_iter)?;
+        let new_a_acc = next_account_info(accounts_iter)?;
+        let new_mint_a_acc = next_account_info(accounts_iter)?;
+        let new_treasury_a_acc = next_account_info(accounts_iter)?;
+        let new_b_acc = next_account_info(accounts_iter)?;
+        let new_mint_b_acc = next_account_info(accounts_iter)?;
+        let new_treasury_b_acc = next_account_info(accounts_iter)?;
+        let splt_program = next_account_info(accounts_iter)?;
+        let splata_program = next_account_info(accounts_iter)?;
+        let swap_program = next_account_info(accounts_iter)?;
+        let sysvar_rent_acc = next_account_info(accounts_iter)?;
+        let system_program = next_account_info(accounts_iter)?;
+        let config_acc = next_account_info(accounts_iter)?;
+
+        let config_data = Self::read_config(program_id, config_acc)?;
+        Self::is_unpaused(&config_data, Config::PAUSE_REMOVE_LIQUIDITY)?;
+        Self::is_unpaused(&config_data, Config::PAUSE_ADD_LIQUIDITY)?;
+        Self::is_trusted(&config_data, swap_program, splt_program, splata_program)?;
+        // Cross-check the accounts against the pools
+        let old_pool_data = Self::read_pool(old_pool_acc, swap_program)?;
+        let new_pool_data = Self::read_pool(new_pool_acc, swap_program)?;
+        Self::is_vault(&old_pool_data, old_vault_acc)?;
+        Self::is_mint_lpt(&old_pool_data, old_mint_lpt_acc)?;
+        Self::is_mint_lpt(&new_pool_data, new_mint_lpt_acc)?;
+        let old_sides = [
+          (old_s_acc, old_mint_s_acc, old_treasury_s_acc),
+          (old_a_acc, old_mint_a_acc, old_treasury_a_acc),
+          (old_b_acc, old_mint_b_acc, old_treasury_b_acc),
+        ];
+        let new_sides = [
+          (new_s_acc, new_mint_s_acc, new_treasury_s_acc),
+          (new_a_acc, new_mint_a_acc, new_treasury_a_acc),
+          (new_b_acc, new_mint_b_acc, new_treasury_b_acc),
+        ];
+        for (pool_data, sides) in
+          [(&old_pool_data, &old_sides), (&new_pool_data, &new_sides)].iter()
+        {
+          let mints = [pool_data.mint_s, pool_data.mint_a, pool_data.mint_b];
+          for (&(acc, mint_acc, treasury_acc), &mint) in sides.iter().zip(mints.iter()) {
+            if *mint_acc.key != mint {
+              return Err(AppError::UnmatchedMint.into());
+            }
+            Self::is_treasury(pool_data, treasury_acc, mint)?;
+            Self::is_unfrozen(&[acc, treasury_acc])?;
+          }
+        }
+        Self::is_unfrozen(&[old_lpt_acc, new_lpt_acc])?;
+        // Legs also held by the new pool go straight into its source accounts
+        let mut targets = [None; 3];
+        for (target, &(old_acc, old_mint_acc, _)) in targets.iter_mut().zip(old_sides.iter()) {
+          *target = new_sides
+            .iter()
+            .position(|(_, new_mint_acc, _)| new_mint_acc.key == old_mint_acc.key);
+          if let Some(j) = *target {
+            if new_sides[j].0.key != old_acc.key {
+              return Err(AppError::UnmatchedSource.into());
+            }
+          }
+        }
+        // The other legs are swapped in the old pool into a shared mint, the primary first
+        let shared = targets
+          .iter()
+          .position(|target| target.is_some())
+          .ok_or(AppError::NoAvailableRoute)?;
+
+        // Initialize destination accounts just in case
+        if !Self::is_rented_and_initialized_acc(new_lpt_acc)? {
+          XSPLATA::initialize_account(
+            payer,
+            new_lpt_acc,
+            payer,
+            new_mint_lpt_acc,
+            system_program,
+            splt_program,
+            sysvar_rent_acc,
+            splata_program,
+            &[],
+          )?;
+        }
+        for &(acc, mint_acc, _) in old_sides.iter().chain(new_sides.iter()) {
+          if !Self::is_rented_and_initialized_acc(acc)? {
+            XSPLATA::initialize_account(
+              payer,
+              acc,
+              payer,
+              mint_acc,
+              system_program,
+              splt_program,
+              sysvar_rent_acc,
+              splata_program,
+              &[],
+            )?;
+          }
+        }
+        let prev_old_lpt = Self::read_amount(old_lpt_acc)?;
+        let prev_new_lpt = Self::read_amount(new_lpt_acc)?;
+        let mut prev_old_amounts = [0; 3];
+        for (prev_amount, &(acc, _, _)) in prev_old_amounts.iter_mut().zip(old_sides.iter()) {
+          *prev_amount = Self::read_amount(acc)?;
+        }
+        let mut prev_new_amounts = [0; 3];
+        for (prev_amount, &(acc, _, _)) in prev_new_amounts.iter_mut().zip(new_sides.iter()) {
+          *prev_amount = Self::read_amount(acc)?;
+        }
+        // Remove Liquidity
+        XSwap::remove_liquidity(
+          lpt,
+          payer,
+          old_pool_acc,
+          old_lpt_acc,
+          old_mint_lpt_acc,
+          old_s_acc,
+          old_treasury_s_acc,
+          old_a_acc,
+          old_treasury_a_acc,
+          old_b_acc,
+          old_treasury_b_acc,
+          old_treasurer,
+          splt_program,
+          swap_program,
+          &[],
+        )?;
+        let mut old_deltas = [0; 3];
+        for (i, &(acc, _, _)) in old_sides.iter().enumerate() {
+          old_deltas[i] = Self::read_amount(acc)?
+            .checked_sub(prev_old_amounts[i])
+            .ok_or(AppError::Overflow)?;
+        }
+        // Swap the legs the new pool does not hold
+        let mut fills = Vec::new();
+        for (i, &(acc, _, treasury_acc)) in old_sides.iter().enumerate() {
+          if targets[i].is_some() || old_deltas[i] == 0 {
+            continue;
+          }
+          fills.push(Self::swap_with_fill(
+            old_deltas[i],
+            0,
+            payer,
+            old_pool_acc,
+            old_vault_acc,
+            acc,
+            treasury_acc,
+            old_sides[shared].0,
+            old_sides[shared].2,
+            old_treasury_s_acc,
+            old_treasurer,
+            splt_program,
+            swap_program,
+          )?);
+        }
+        let mut new_deltas = [0; 3];
+        for (i, &(acc, _, _)) in new_sides.iter().enumerate() {
+          new_deltas[i] = Self::read_amount(acc)?
+            .checked_sub(prev_new_amounts[i])
+            .ok_or(AppError::Overflow)?;
+        }
+        // Add Liquidity
+        XSwap::add_liquidity(
+          new_deltas[0],
+          new_deltas[1],
+          new_deltas[2],
+          payer,
+          new_pool_acc,
+          new_lpt_acc,
+          new_mint_lpt_acc,
+          new_s_acc,
+          new_treasury_s_acc,
+          new_a_acc,
+          new_treasury_a_acc,
+          new_b_acc,
+          new_treasury_b_acc,
+          new_treasurer,
+          splt_program,
+          swap_program,
+          &[],
+        )?;
+        let new_lpt = Self::read_amount(new_lpt_acc)?
+          .checked_sub(prev_new_lpt)
+          .ok_or(AppError::Overflow)?;
+        if new_lpt < min_lpt {
+          return Err(AppError::InsufficientAmountOut.into());
+        }
+        // Return data: the minted lpt of the new pool
+        set_return_data(&new_lpt.to_le_bytes());
+        Event {
+          kind,
+          authority: *payer.key,
+          data: EventData::Liquidity {
+            pool: *old_pool_acc.key,
+            lpt: prev_old_lpt
+              .checked_sub(Self::read_amount(old_lpt_acc)?)
+              .ok_or(AppError::Overflow)?,
+            amount_s: old_deltas[0],
+            amount_a: old_deltas[1],
+            amount_b: old_deltas[2],
+          },
+        }
+        .emit();
+        Event {
+          kind,
+          authority: *payer.key,
+          data: EventData::Swap { fills },
+        }
+        .emit();
+        Event {
+          kind,
+          authority: *payer.key,
+          data: EventData::Liquidity {
+            pool: *new_pool_acc.key,
+            lpt: new_lpt,
+            amount_s: new_deltas[0],
+            amount_a: new_deltas[1],
+            amount_b: new_deltas[2],
+          },
+        }
+        .emit();
+
+        Ok(())
+      }
+    }
+  }
+
+  // Shared by AddLiquidity, AddLiquidityMinOut and AddLiquidityBalanced, a zero
+  // min_lpt is unguarded and the balanced mode caps the deltas to the pool ratio
+  #[allow(clippy::too_many_arguments)]
+  pub fn add_liquidity(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    kind: u8,
+    delta_s: u64,
+    delta_a: u64,
+    delta_b: u64,
+    min_lpt: u64,
+    is_balanced: bool,
+  ) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let payer = next_account_info(accounts_iter)?;
+    let pool_acc = next_account_info(accounts_iter)?;
+    let lpt_acc = next_account_info(accounts_iter)?;
+    let mint_lpt_acc = next_account_info(accounts_iter)?;
+    let src_s_acc = next_account_info(accounts_iter)?;
+    let treasury_s_acc = next_account_info(accounts_iter)?;
+    let src_a_acc = next_account_info(accounts_iter)?;
+    let treasury_a_acc = next_account_info(accounts_iter)?;
+    let src_b_acc = next_account_info(accounts_iter)?;
+    let treasury_b_acc = next_account_info(accounts_iter)?;
+    let treasurer = next_account_info(accounts_iter)?;
+    let splt_program = next_account_info(accounts_iter)?;
+    let splata_program = next_account_info(accounts_iter)?;
+    let swap_program = next_account_info(accounts_iter)?;
+    let sysvar_rent_acc = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+    let config_acc = next_account_info(accounts_iter)?;
+
+    let config_data = Self::read_config(program_id, config_acc)?;
+    Self::is_unpaused(&config_data, Config::PAUSE_ADD_LIQUIDITY)?;
+    Self::is_trusted(&config_data, swap_program, splt_program, splata_program)?;
+    // Cross-check the accounts against the pool
+    let pool_data = Self::read_pool(pool_acc, swap_program)?;
+    Self::is_mint_lpt(&pool_data, mint_lpt_acc)?;
+    Self::is_treasury(&pool_data, treasury_s_acc, pool_data.mint_s)?;
+    Self::is_treasury(&pool_data, treasury_a_acc, pool_data.mint_a)?;
+    Self::is_treasury(&pool_data, treasury_b_acc, pool_data.mint_b)?;
+    Self::is_unfrozen(&[
+      lpt_acc,
+      src_s_acc,
+      treasury_s_acc,
+      src_a_acc,
+      treasury_a_acc,
+      src_b_acc,
+      treasury_b_acc,
+    ])?;
+    // Deposit only what the current ratio of the reserves allows
+    let deltas = [delta_s, delta_a, delta_b];
+    let [delta_s, delta_a, delta_b] = if is_balanced {
+      Oracle::balance_deltas(
+        deltas,
+        [
+          pool_data.reserve_s,
+          pool_data.reserve_a,
+          pool_data.reserve_b,
+        ],
+      )
+      .ok_or(AppError::Overflow)?
+    } else {
+      deltas
+    };
+
+    // Initialize destination account just in case
+    if !Self::is_rented_and_initialized_acc(lpt_acc)? {
+      XSPLATA::initialize_account(
+        payer,
+        lpt_acc,
+        payer,
+        mint_lpt_acc,
+        system_program,
+        splt_program,
+        sysvar_rent_acc,
+        splata_program,
+        &[],
+      )?;
+    }
+    let prev_lpt = Self::read_amount(lpt_acc)?;
+    let prev_amount_s = Self::read_amount(src_s_acc)?;
+    let prev_amount_a = Self::read_amount(src_a_acc)?;
+    let prev_amount_b = Self::read_amount(src_b_acc)?;
+    // Add Liquidity
+    XSwap::add_liquidity(
+      delta_s,
+      delta_a,
+      delta_b,
+      payer,
+      pool_acc,
+      lpt_acc,
+      mint_lpt_acc,
+      src_s_acc,
+      treasury_s_acc,
+      src_a_acc,
+      treasury_a_acc,
+      src_b_acc,
+      treasury_b_acc,
+      treasurer,
+      splt_program,
+      swap_program,
+      &[],
+    )?;
+    let lpt = Self::read_amount(lpt_acc)?
+      .checked_sub(prev_lpt)
+      .ok_or(AppError::Overflow)?;
+    if lpt < min_lpt {
+      return Err(AppError::InsufficientAmountOut.into());
+    }
+    let amount_s = prev_amount_s
+      .checked_sub(Self::read_amount(src_s_acc)?)
+      .ok_or(AppError::Overflow)?;
+    let amount_a = prev_amount_a
+      .checked_sub(Self::read_amount(src_a_acc)?)
+      .ok_or(AppError::Overflow)?;
+    let amount_b = prev_amount_b
+      .checked_sub(Self::read_amount(src_b_acc)?)
+      .ok_or(AppError::Overflow)?;
+    if is_balanced {
+      // Return data: the minted lpt followed by the unused s, a and b
+      let unused_s = deltas[0].saturating_sub(amount_s);
+      let unused_a = deltas[1].saturating_sub(amount_a);
+      let unused_b = deltas[2].saturating_sub(amount_b);
+      msg!(
+        "Unused amounts: s {}, a {}, b {}",
+        unused_s,
+        unused_a,
+        unused_b
+      );
+      let mut data = lpt.to_le_bytes().to_vec();
+      data.extend_from_slice(&unused_s.to_le_bytes());
+      data.extend_from_slice(&unused_a.to_le_bytes());
+      data.extend_from_slice(&unused_b.to_le_bytes());
+      set_return_data(&data);
+    } else {
+      // Return data: the minted lpt
+      set_return_data(&lpt.to_le_bytes());
+    }
+    Event {
+      kind,
+      authority: *payer.key,
+      data: EventData::Liquidity {
+        pool: *pool_acc.key,
+        lpt,
+        amount_s,
+        amount_a,
+        amount_b,
+      },
+    }
+    .emit();
+
+    Ok(())
+  }
+
+  // Shared by RemoveLiquidity and RemoveLiquidityMinOut, zero minimums are unguarded
+  pub fn remove_liquidity(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    kind: u8,
+    lpt: u64,
+    min_s: u64,
+    min_a: u64,
+    min_b: u64,
+  ) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let payer = next_account_info(accounts_iter)?;
+    let pool_acc = next_account_info(accounts_iter)?;
+    let lpt_acc = next_account_info(accounts_iter)?;
+    let mint_lpt_acc = next_account_info(accounts_iter)?;
+    let dst_s_acc = next_account_info(accounts_iter)?;
+    let mint_s_acc = next_account_info(accounts_iter)?;
+    let treasury_s_acc = next_account_info(accounts_iter)?;
+    let dst_a_acc = next_account_info(accounts_iter)?;
+    let mint_a_acc = next_account_info(accounts_iter)?;
+    let treasury_a_acc = next_account_info(accounts_iter)?;
+    let dst_b_acc = next_account_info(accounts_iter)?;
+    let mint_b_acc = next_account_info(accounts_iter)?;
+    let treasury_b_acc = next_account_info(accounts_iter)?;
+    let treasurer = next_account_info(accounts_iter)?;
+    let splt_program = next_account_info(accounts_iter)?;
+    let splata_program = next_account_info(accounts_iter)?;
+    let swap_program = next_account_info(accounts_iter)?;
+    let sysvar_rent_acc = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+    let config_acc = next_account_info(accounts_iter)?;
+
+    let config_data = Self::read_config(program_id, config_acc)?;
+    Self::is_unpaused(&config_data, Config::PAUSE_REMOVE_LIQUIDITY)?;
+    Self::is_trusted(&config_data, swap_program, splt_program, splata_program)?;
+    // Cross-check the accounts against the pool
+    let pool_data = Self::read_pool(pool_acc, swap_program)?;
+    Self::is_mint_lpt(&pool_data, mint_lpt_acc)?;
+    if pool_data.mint_s != *mint_s_acc.key
+      || pool_data.mint_a != *mint_a_acc.key
+      || pool_data.mint_b != *mint_b_acc.key
+    {
+      return Err(AppError::UnmatchedMint.into());
+    }
+    Self::is_treasury(&pool_data, treasury_s_acc, pool_data.mint_s)?;
+    Self::is_treasury(&pool_data, treasury_a_acc, pool_data.mint_a)?;
+    Self::is_treasury(&pool_data, treasury_b_acc, pool_data.mint_b)?;
+    Self::is_unfrozen(&[
+      lpt_acc,
+      dst_s_acc,
+      treasury_s_acc,
+      dst_a_acc,
+      treasury_a_acc,
+      dst_b_acc,
+      treasury_b_acc,
+    ])?;
+
+    // Initialize destination account just in case
+    if !Self::is_rented_and_initialized_acc(dst_s_acc)? {
+      XSPLATA::initialize_account(
+        payer,
+        dst_s_acc,
+        payer,
+        mint_s_acc,
+        system_program,
+        splt_program,
+        sysvar_rent_acc,
+        splata_program,
+        &[],
+      )?;
+    }
+    if !Self::is_rented_and_initialized_acc(dst_a_acc)? {
+      XSPLATA::initialize_account(
+        payer,
+        dst_a_acc,
+        payer,
+        mint_a_acc,
+        system_program,
+        splt_program,
+        sysvar_rent_acc,
+        splata_program,
+        &[],
+      )?;
+    }
+    if !Self::is_rented_and_initialized_acc(dst_b_acc)? {
+      XSPLATA::initialize_account(
+        payer,
+        dst_b_acc,
+        payer,
+        mint_b_acc,
+        system_program,
+        splt_program,
+        sysvar_rent_acc,
+        splata_program,
+        &[],
+      )?;
+    }
+    let prev_lpt = Self::read_amount(lpt_acc)?;
+    let prev_amount_s = Self::read_amount(dst_s_acc)?;
+    let prev_amount_a = Self::read_amount(dst_a_acc)?;
+    let prev_amount_b = Self::read_amount(dst_b_acc)?;
+    // Remove Liquidity
+    XSwap::remove_liquidity(
+      lpt,
+      payer,
+      pool_acc,
+      lpt_acc,
+      mint_lpt_acc,
+      dst_s_acc,
+      treasury_s_acc,
+      dst_a_acc,
+      treasury_a_acc,
+      dst_b_acc,
+      treasury_b_acc,
+      treasurer,
+      splt_program,
+      swap_program,
+      &[],
+    )?;
+    let lpt = prev_lpt
+      .checked_sub(Self::read_amount(lpt_acc)?)
+      .ok_or(AppError::Overflow)?;
+    let amount_s = Self::read_amount(dst_s_acc)?
+      .checked_sub(prev_amount_s)
+      .ok_or(AppError::Overflow)?;
+    let amount_a = Self::read_amount(dst_a_acc)?
+      .checked_sub(prev_amount_a)
+      .ok_or(AppError::Overflow)?;
+    let amount_b = Self::read_amount(dst_b_acc)?
+      .checked_sub(prev_amount_b)
+      .ok_or(AppError::Overflow)?;
+    if amount_s < min_s || amount_a < min_a || amount_b < min_b {
+      return Err(AppError::InsufficientAmountOut.into());
+    }
+    // Return data: the withdrawn amounts of s, a and b
+    let mut data = Vec::with_capacity(24);
+    data.extend_from_slice(&amount_s.to_le_bytes());
+    data.extend_from_slice(&amount_a.to_le_bytes());
+    data.extend_from_slice(&amount_b.to_le_bytes());
+    set_return_data(&data);
+    Event {
+      kind,
+      authority: *payer.key,
+      data: EventData::Liquidity {
+        pool: *pool_acc.key,
+        lpt,
+        amount_s,
+        amount_a,
+        amount_b,
+      },
+    }
+    .emit();
+
+    Ok(())
+  }
+
+  #[allow(clippy::too_many_arguments)]
+  pub fn swap_along<'b, 'a>(
+    amount: u64,
+    limits: &[u64],
+    bid_amounts: Option<&[u64]>,
+    payer: &'b AccountInfo<'a>,
+    src_acc: &'b AccountInfo<'a>,
+    route: &[Hop<'b, 'a>],
+    splt_program: &'b AccountInfo<'a>,
+    splata_program: &'b AccountInfo<'a>,
+    swap_program: &'b AccountInfo<'a>,
+    sysvar_rent_acc: &'b AccountInfo<'a>,
+    system_program: &'b AccountInfo<'a>,
+    temporary: &mut Vec<&'b AccountInfo<'a>>,
+  ) -> Result<Vec<Fill>, ProgramError> {
+    let mut bid_acc = src_acc;
+    let mut bid_amount = amount;
+    let mut fills = Vec::with_capacity(route.len());
+    for (i, (hop, &limit)) in route.iter().zip(limits).enumerate() {
+      // Exact-output routes swap the fixed amount of every hop
+      if let Some(bid_amounts) = bid_amounts {
+        bid_amount = bid_amounts[i];
+      }
+      // Initialize hop destination account just in case
+      if !Self::is_rented_and_initialized_acc(hop.dst_acc)? {
+        XSPLATA::initialize_account(
+          payer,
+          hop.dst_acc,
+          payer,
+          hop.mint_ask_acc,
+          system_program,
+          splt_program,
+          sysvar_rent_acc,
+          splata_program,
+          &[],
+        )?;
+        // Only middle accounts are temporary, the last one is the output
+        if i + 1 < route.len() {
+          temporary.push(hop.dst_acc);
+        }
+      }
+      // Routing
+      let fill = Self::swap_with_fill(
+        bid_amount,
+        limit,
+        payer,
+        hop.pool_acc,
+        hop.vault_acc,
+        bid_acc,
+        hop.treasury_bid_acc,
+        hop.dst_acc,
+        hop.treasury_ask_acc,
+        hop.treasury_sen_acc,
+        hop.treasurer,
+        splt_program,
+        swap_program,
+      )?;
+      // The next hop swaps exactly what this hop delivered
+      bid_acc = hop.dst_acc;
+      bid_amount = fill.amount_out;
+      fills.push(fill);
+    }
+    Ok(fills)
+  }
+
+  // Funds the source account with lamports of the payer and returns whether
+  // the account was created for this instruction
+  #[allow(clippy::too_many_arguments)]
+  pub fn wrap_sol<'a>(
+    amount: u64,
+    payer: &AccountInfo<'a>,
+    src_acc: &AccountInfo<'a>,
+    native_mint_acc: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    splt_program: &AccountInfo<'a>,
+    sysvar_rent_acc: &AccountInfo<'a>,
+    splata_program: &AccountInfo<'a>,
+  ) -> Result<bool, ProgramError> {
+    if *native_mint_acc.key != native_mint::id() {
+      return Err(AppError::NotNativeAccount.into());
+    }
+    let is_temporary = !Self::is_rented_and_initialized_acc(src_acc)?;
+    if is_temporary {
+      XSPLATA::initialize_account(
+        payer,
+        src_acc,
+        payer,
+        native_mint_acc,
+        system_program,
+        splt_program,
+        sysvar_rent_acc,
+        splata_program,
+        &[],
+      )?;
+    }
+    Self::is_native(src_acc)?;
+    invoke_signed(
+      &system_instruction::transfer(payer.key, src_acc.key, amount),
+      &[payer.clone(), src_acc.clone(), system_program.clone()],
+      &[],
+    )?;
+    XSPLT::sync_native(src_acc, splt_program, &[])?;
+    Ok(is_temporary)
+  }
+
+  // Closes the accounts created for the instruction once they are empty
+  pub fn close_temporary<'a>(
+    flags: u8,
+    temporary: &[&AccountInfo<'a>],
+    payer: &AccountInfo<'a>,
+    splt_program: &AccountInfo<'a>,
+  ) -> ProgramResult {
+    if flags & AppInstruction::CLOSE_TEMPORARY == 0 {
+      return Ok(());
+    }
+    for acc in temporary {
+      if Self::read_amount(acc)? == 0 {
+        XSPLT::close_account(acc, payer, payer, splt_program, &[])?;
+      }
+    }
+    Ok(())
+  }
+
+  // Closes the native account and returns its lamports to the payer
+  pub fn unwrap_sol<'a>(
+    payer: &AccountInfo<'a>,
+    acc: &AccountInfo<'a>,
+    splt_program: &AccountInfo<'a>,
+  ) -> ProgramResult {
+    Self::is_native(acc)?;
+    XSPLT::close_account(acc, payer, payer, splt_program, &[])
+  }
+
+  pub fn is_native(acc: &AccountInfo) -> ProgramResult {
+    if !Account::unpack(&acc.data.borrow())?.is_native() {
+      return Err(AppError::NotNativeAccount.into());
+    }
+    Ok(())
+  }
+
+  // Return data: the output amount, then the output amount of every hop
+  pub fn return_fills(amount_out: u64, fills: &[Fill]) {
+    let mut data = Vec::with_capacity(8 + 8 * fills.len());
+    data.extend_from_slice(&amount_out.to_le_bytes());
+    for fill in fills.iter() {
+      data.extend_from_slice(&fill.amount_out.to_le_bytes());
+    }
+    set_return_data(&data);
+  }
+
+  // Swaps through a pool and measures what was filled
+  #[allow(clippy::too_many_arguments)]
+  pub fn swap_with_fill<'a>(
+    amount: u64,
+    limit: u64,
+    payer: &AccountInfo<'a>,
+    pool_acc: &AccountInfo<'a>,
+    vault_acc: &AccountInfo<'a>,
+    src_acc: &AccountInfo<'a>,
+    treasury_bid_acc: &AccountInfo<'a>,
+    dst_acc: &AccountInfo<'a>,
+    treasury_ask_acc: &AccountInfo<'a>,
+    treasury_sen_acc: &AccountInfo<'a>,
+    treasurer: &AccountInfo<'a>,
+    splt_program: &AccountInfo<'a>,
+    swap_program: &AccountInfo<'a>,
+  ) -> Result<Fill, ProgramError> {
+    let pool_data = Pool::unpack(&pool_acc.data.borrow())?;
+    let bid_mint = Account::unpack(&src_acc.data.borrow())?.mint;
+    let ask_mint = Account::unpack(&dst_acc.data.borrow())?.mint;
+    // Fees are only estimated for the event, so they never fail the swap
+    let (estimated_fee, estimated_earning) = Self::quote(&pool_data, bid_mint, ask_mint, amount)
+      .map(|(_, fee, earning, _)| (fee, earning))
+      .unwrap_or((0, 0));
+    let prev_ask_amount = Self::read_amount(dst_acc)?;
+    XSwap::swap(
+      amount,
+      limit,
+      payer,
+      pool_acc,
+      vault_acc,
+      src_acc,
+      treasury_bid_acc,
+      dst_acc,
+      treasury_ask_acc,
+      treasury_sen_acc,
+      treasurer,
+      splt_program,
+      swap_program,
+      &[],
+    )?;
+    let amount_out = Self::read_amount(dst_acc)?
+      .checked_sub(prev_ask_amount)
+      .ok_or(AppError::Overflow)?;
+    Ok(Fill {
+      pool: *pool_acc.key,
+      bid_mint,
+      ask_mint,
+      amount_in: amount,
+      amount_out,
+      estimated_fee,
+      estimated_earning,
+    })
+  }
+
+  pub fn read_config(
+    program_id: &Pubkey,
+    config_acc: &AccountInfo,
+  ) -> Result<Config, ProgramError> {
+    // Only the config account can be owned by the router
+    if config_acc.owner != program_id {
+      return Err(AppError::InvalidConfig.into());
+    }
+    let config_data = Config::unpack(&config_acc.data.borrow())?;
+    Ok(config_data)
+  }
+
+  pub fn is_admin(config_data: &Config, admin: &AccountInfo) -> ProgramResult {
+    if !admin.is_signer {
+      return Err(ProgramError::MissingRequiredSignature);
+    }
+    if config_data.admin != *admin.key {
+      return Err(AppError::InvalidAdmin.into());
+    }
+    Ok(())
+  }
+
+  pub fn is_upgrade_authority(
+    program_id: &Pubkey,
+    program_data_acc: &AccountInfo,
+    authority: &AccountInfo,
+  ) -> ProgramResult {
+    if !authority.is_signer {
+      return Err(ProgramError::MissingRequiredSignature);
+    }
+    let (program_data_key, _) =
+      Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id());
+    if program_data_key != *program_data_acc.key
+      || *program_data_acc.owner != bpf_loader_upgradeable::id()
+    {
+      return Err(AppError::InvalidUpgradeAuthority.into());
+    }
+    // ProgramData is a u32 tag of 3, the slot and an optional authority
+    let data = program_data_acc.data.borrow();
+    let key = data
+      .get(13..45)
+      .and_then(|slice| slice.try_into().ok())
+      .map(Pubkey::new_from_array);
+    let upgrade_authority = match (data.get(..4), data.get(12), key) {
+      (Some(&[3, 0, 0, 0]), Some(&1), Some(key)) => key,
+      _ => return Err(AppError::InvalidUpgradeAuthority.into()),
+    };
+    if upgrade_authority != *authority.key {
+      return Err(AppError::InvalidUpgradeAuthority.into());
+    }
+    Ok(())
+  }
+
+  pub fn is_unpaused(config_data: &Config, operation: u8) -> ProgramResult {
+    if config_data.is_paused(operation) {
+      return Err(AppError::Paused.into());
+    }
+    Ok(())
+  }
+
+  pub fn is_trusted(
+    config_data: &Config,
+    swap_program: &AccountInfo,
+    splt_program: &AccountInfo,
+    splata_program: &AccountInfo,
+  ) -> ProgramResult {
+    // The payer signature is forwarded, so only CPI into known programs
+    if config_data.swap_program != *swap_program.key
+      || config_data.splt_program != *splt_program.key
+      || config_data.splata_program != *splata_program.key
+    {
+      return Err(AppError::IncorrectProgramId.into());
+    }
+    Ok(())
+  }
+
+  pub fn unpack_pool(
+    pool_acc: &AccountInfo,
+    swap_program: &AccountInfo,
+  ) -> Result<Pool, ProgramError> {
+    if pool_acc.owner != swap_program.key {
+      return Err(AppError::InvalidPoolOwner.into());
+    }
+    Pool::unpack(&pool_acc.data.borrow())
+  }
+
+  pub fn read_pool(
+    pool_acc: &AccountInfo,
+    swap_program: &AccountInfo,
+  ) -> Result<Pool, ProgramError> {
+    let pool_data = Self::unpack_pool(pool_acc, swap_program)?;
+    if pool_data.is_frozen() {
+      return Err(AppError::PoolFrozen.into());
+    }
+    Ok(pool_data)
+  }
+
+  pub fn is_vault(pool_data: &Pool, vault_acc: &AccountInfo) -> ProgramResult {
+    if pool_data.vault != *vault_acc.key {
+      return Err(AppError::UnmatchedVault.into());
+    }
+    Ok(())
+  }
+
+  pub fn is_mint_lpt(pool_data: &Pool, mint_lpt_acc: &AccountInfo) -> ProgramResult {
+    if pool_data.mint_lpt != *mint_lpt_acc.key {
+      return Err(AppError::UnmatchedMintLpt.into());
+    }
+    Ok(())
+  }
+
+  // Returns the mint held by a treasury of the pool
+  pub fn treasury_mint(
+    pool_data: &Pool,
+    treasury_acc: &AccountInfo,
+  ) -> Result<Pubkey, ProgramError> {
+    let (index, _) = pool_data
+      .get_reserve(treasury_acc.key)
+      .ok_or(AppError::UnmatchedTreasury)?;
+    let mint = match index {
+      0 => pool_data.mint_s,
+      1 => pool_data.mint_a,
+      _ => pool_data.mint_b,
+    };
+    Ok(mint)
+  }
+
+  pub fn is_treasury(pool_data: &Pool, treasury_acc: &AccountInfo, mint: Pubkey) -> ProgramResult {
+    Self::parse_reserve(pool_data, mint).ok_or(AppError::UnmatchedMint)?;
+    if Self::treasury_mint(pool_data, treasury_acc)? != mint {
+      return Err(AppError::UnmatchedTreasury.into());
+    }
+    Ok(())
+  }
+
+  // Cross-checks the hop accounts against the pool and returns its data
+  pub fn read_hop(
+    hop: &Hop,
+    bid_mint: Pubkey,
+    swap_program: &AccountInfo,
+  ) -> Result<Pool, ProgramError> {
+    let pool_data = Self::read_pool(hop.pool_acc, swap_program)?;
+    Self::is_vault(&pool_data, hop.vault_acc)?;
+    Self::is_treasury(&pool_data, hop.treasury_bid_acc, bid_mint)?;
+    Self::is_treasury(&pool_data, hop.treasury_ask_acc, *hop.mint_ask_acc.key)?;
+    Self::is_treasury(&pool_data, hop.treasury_sen_acc, pool_data.mint_s)?;
+    Self::is_unfrozen(&[
+      hop.treasury_bid_acc,
+      hop.treasury_ask_acc,
+      hop.treasury_sen_acc,
+      hop.dst_acc,
+    ])?;
+    Ok(pool_data)
+  }
+
+  // Cross-checks every hop of a route starting from the source account
+  pub fn read_route(
+    src_acc: &AccountInfo,
+    route: &[Hop],
+    swap_program: &AccountInfo,
+  ) -> Result<Vec<Pool>, ProgramError> {
+    Self::is_unfrozen(&[src_acc])?;
+    let mut bid_mint = Account::unpack(&src_acc.data.borrow())?.mint;
+    let mut pools = Vec::with_capacity(route.len());
+    for hop in route.iter() {
+      pools.push(Self::read_hop(hop, bid_mint, swap_program)?);
+      bid_mint = *hop.mint_ask_acc.key;
+    }
+    Ok(pools)
+  }
+
+  // Accounts that do not exist yet cannot be frozen
+  pub fn is_unfrozen(accs: &[&AccountInfo]) -> ProgramResult {
+    for acc in accs.iter() {
+      if Self::is_frozen_acc(acc)? {
+        return Err(AppError::AccountFrozen.into());
+      }
+    }
+    Ok(())
+  }
+
+  pub fn is_frozen_acc(acc: &AccountInfo) -> Result<bool, ProgramError> {
+    if !Self::is_rented_and_initialized_acc(acc)? {
+      return Ok(false);
+    }
+    Ok(Account::unpack(&acc.data.borrow())?.is_frozen())
+  }
+
+  pub fn is_rented_and_initialized_acc(acc: &AccountInfo) -> Result<bool, ProgramError> {
+    if acc.data_is_empty() {
+      return Ok(false);
+    }
+    let acc_data = Account::unpack_unchecked(&acc.data.borrow())?;
+    Ok(acc_data.is_initialized())
+  }
+
+  pub fn read_amount(acc: &AccountInfo) -> Result<u64, ProgramError> {
+    let acc_data = Account::unpack(&acc.data.borrow())?;
+    Ok(acc_data.amount)
+  }
+
+  pub fn parse_reserve(pool_data: &Pool, mint: Pubkey) -> Option<u64> {
+    if pool_data.mint_a == mint {
+      Some(pool_data.reserve_a)
+    } else if pool_data.mint_b == mint {
+      Some(pool_data.reserve_b)
+    } else if pool_data.mint_s == mint {
+      Some(pool_data.reserve_s)
+    } else {
+      None
+    }
+  }
+
+  pub fn estimate_ask_amount(
+    pool_data: &Pool,
+    bid_mint: Pubkey,
+    ask_mint: Pubkey,
+    amount: u64,
+  ) -> Result<u64, ProgramError> {
+    let (ask_amount, _, _, _) = Self::quote(pool_data, bid_mint, ask_mint, amount)?;
+    Ok(ask_amount)
+  }
+
+  // Returns the ask amount, fee, earning and price impact of a swap
+  pub fn quote(
+    pool_data: &Pool,
+    bid_mint: Pubkey,
+    ask_mint: Pubkey,
+    amount: u64,
+  ) -> Result<(u64, u64, u64, u64), ProgramError> {
+    let bid_reserve =
+      Self::parse_reserve(pool_data, bid_mint).ok_or(AppError::CannotFindReserves)?;
+    let ask_reserve =
+      Self::parse_reserve(pool_data, ask_mint).ok_or(AppError::CannotFindReserves)?;
+    // Swaps touching the primary mint are exempted from earning
+    let is_exempted = bid_mint == pool_data.mint_s || ask_mint == pool_data.mint_s;
+    let new_bid_reserve = bid_reserve.checked_add(amount).ok_or(AppError::Overflow)?;
+    let (new_ask_reserve, ask_amount, earning) =
+      Oracle::curve_in_fee(new_bid_reserve, bid_reserve, ask_reserve, is_exempted)
+        .ok_or(AppError::Overflow)?;
+    let new_ask_reserve_without_fee =
+      Oracle::curve(new_bid_reserve, bid_reserve, ask_reserve).ok_or(AppError::Overflow)?;
+    let fee = new_ask_reserve
+      .checked_sub(new_ask_reserve_without_fee)
+      .ok_or(AppError::Overflow)?;
+    let price_impact =
+      Oracle::price_impact(new_bid_reserve, bid_reserve, ask_reserve).ok_or(AppError::Overflow)?;
+    Ok((ask_amount, fee, earning, price_impact))
+  }
+
+  pub fn estimate_bid_amount(
+    pool_data: &Pool,
+    bid_mint: Pubkey,
+    ask_mint: Pubkey,
+    amount: u64,
+  ) -> Result<u64, ProgramError> {
+    let bid_reserve =
+      Self::parse_reserve(pool_data, bid_mint).ok_or(AppError::CannotFindReserves)?;
+    let ask_reserve =
+      Self::parse_reserve(pool_data, ask_mint).ok_or(AppError::CannotFindReserves)?;
+    let is_exempted = bid_mint == pool_data.mint_s || ask_mint == pool_data.mint_s;
+    let new_bid_reserve =
+      Oracle::inverse_curve_in_fee(amount, bid_reserve, ask_reserve, is_exempted)
+        .ok_or(AppError::Overflow)?;
+    let bid_amount = new_bid_reserve
+      .checked_sub(bid_reserve)
+      .ok_or(AppError::Overflow)?;
+    Ok(bid_amount)
   }
 }
@@ -7,6 +7,11 @@ use solana_program::{
   pubkey::Pubkey,
 };
 
+/// Mint of wrapped SOL
+pub mod native_mint {
+  solana_program::declare_id!("So11111111111111111111111111111111111111112");
+}
+
 ///
 /// Account struct
 ///
@@ -128,6 +133,7 @@ pub enum AccountState {
   Frozen,
 }
 
+#[allow(clippy::derivable_impls)]
 impl Default for AccountState {
   fn default() -> Self {
     AccountState::Uninitialized
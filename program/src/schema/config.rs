@@ -0,0 +1,126 @@
+use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
+use solana_program::{
+  program_error::ProgramError,
+  program_pack::{IsInitialized, Pack, Sealed},
+  pubkey::Pubkey,
+};
+
+///
+/// Config struct
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Config {
+  pub version: u8,
+  pub is_initialized: bool,
+  pub admin: Pubkey,
+  pub max_hops: u8,
+  pub paused: u8,
+  pub swap_program: Pubkey,
+  pub splt_program: Pubkey,
+  pub splata_program: Pubkey,
+}
+
+///
+/// Config implementation
+///
+impl Config {
+  // Current layout version
+  pub const VERSION: u8 = 1;
+  // Seed of the config address
+  pub const SEED: &'static [u8] = b"config";
+  // Flags of the operations that can be paused separately
+  pub const PAUSE_SWAP: u8 = 1 << 0;
+  pub const PAUSE_ROUTE: u8 = 1 << 1;
+  pub const PAUSE_ADD_LIQUIDITY: u8 = 1 << 2;
+  pub const PAUSE_REMOVE_LIQUIDITY: u8 = 1 << 3;
+  // Derive the config address of the router
+  pub fn address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[Self::SEED], program_id)
+  }
+  // Is the operation paused
+  pub fn is_paused(&self, operation: u8) -> bool {
+    self.paused & operation != 0
+  }
+}
+
+///
+/// Sealed trait
+///
+impl Sealed for Config {}
+
+///
+/// IsInitialized trait
+///
+impl IsInitialized for Config {
+  fn is_initialized(&self) -> bool {
+    self.is_initialized
+  }
+}
+
+///
+/// Pack trait
+///
+impl Pack for Config {
+  // Fixed length
+  const LEN: usize = 1 + 1 + 32 + 1 + 1 + 3 * 32;
+  // Unpack data from [u8] to the data struct
+  fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+    let src = array_ref![src, 0, 132];
+    let (
+      version,
+      is_initialized,
+      admin,
+      max_hops,
+      paused,
+      swap_program,
+      splt_program,
+      splata_program,
+    ) = array_refs![src, 1, 1, 32, 1, 1, 32, 32, 32];
+    Ok(Config {
+      version: version[0],
+      is_initialized: match is_initialized {
+        [0] => false,
+        [1] => true,
+        _ => return Err(ProgramError::InvalidAccountData),
+      },
+      admin: Pubkey::new_from_array(*admin),
+      max_hops: max_hops[0],
+      paused: paused[0],
+      swap_program: Pubkey::new_from_array(*swap_program),
+      splt_program: Pubkey::new_from_array(*splt_program),
+      splata_program: Pubkey::new_from_array(*splata_program),
+    })
+  }
+  // Pack data from the data struct to [u8]
+  fn pack_into_slice(&self, dst: &mut [u8]) {
+    let dst = array_mut_ref![dst, 0, 132];
+    let (
+      dst_version,
+      dst_is_initialized,
+      dst_admin,
+      dst_max_hops,
+      dst_paused,
+      dst_swap_program,
+      dst_splt_program,
+      dst_splata_program,
+    ) = mut_array_refs![dst, 1, 1, 32, 1, 1, 32, 32, 32];
+    let &Config {
+      version,
+      is_initialized,
+      ref admin,
+      max_hops,
+      paused,
+      ref swap_program,
+      ref splt_program,
+      ref splata_program,
+    } = self;
+    *dst_version = [version];
+    *dst_is_initialized = [is_initialized as u8];
+    dst_admin.copy_from_slice(admin.as_ref());
+    *dst_max_hops = [max_hops];
+    *dst_paused = [paused];
+    dst_swap_program.copy_from_slice(swap_program.as_ref());
+    dst_splt_program.copy_from_slice(splt_program.as_ref());
+    dst_splata_program.copy_from_slice(splata_program.as_ref());
+  }
+}
@@ -1,3 +1,4 @@
 pub mod account;
+pub mod config;
 pub mod mint;
 pub mod pool;
@@ -17,6 +17,7 @@ pub enum PoolState {
   Initialized,
   Frozen,
 }
+#[allow(clippy::derivable_impls)]
 impl Default for PoolState {
   fn default() -> Self {
     PoolState::Uninitialized